@@ -0,0 +1,36 @@
+#![cfg_attr(feature = "nightly", feature(test))]
+
+extern crate mxfinfo;
+#[cfg(feature = "nightly")]
+extern crate test;
+
+#[cfg(feature = "nightly")]
+mod benches {
+    use mxfinfo::{DataModel, MXFInfo};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use test::Bencher;
+
+    fn sample_file() -> PathBuf {
+        PathBuf::from("samples").join("domdom.mov.V159CD0127V.mxf")
+    }
+
+    #[bench]
+    fn bench_from_file(b: &mut Bencher) {
+        let filename = sample_file();
+        b.iter(|| MXFInfo::from_file(filename.as_path()).unwrap());
+    }
+
+    #[bench]
+    fn bench_probe(b: &mut Bencher) {
+        let filename = sample_file();
+        b.iter(|| MXFInfo::probe(filename.as_path()).unwrap());
+    }
+
+    #[bench]
+    fn bench_probe_with_model(b: &mut Bencher) {
+        let filename = sample_file();
+        let model = Arc::new(DataModel::new().unwrap());
+        b.iter(|| MXFInfo::probe_with_model(filename.as_path(), &model).unwrap());
+    }
+}
@@ -1,10 +1,19 @@
 extern crate pkg_config;
 
 fn main() {
-    let lib_mxfinfo = pkg_config::probe_library("libMXF-1.0");
-    if lib_mxfinfo.is_err() {
-        panic!("Could not find MediaInfo via pkgconfig");
-    } else {
-        // panic!("{:?}", lib_mediainfo);
+    // The data model structs in src/ffi/mxf.rs mirror mxf/mxf_data_model.h of libMXF 1.0
+    let lib_mxfinfo = pkg_config::Config::new()
+        .atleast_version("1.0")
+        .probe("libMXF-1.0");
+    match lib_mxfinfo {
+        Ok(library) => {
+            if !library.version.starts_with("1.0") {
+                panic!(
+                    "libMXF {} is not supported, the bindings follow libMXF 1.0",
+                    library.version
+                );
+            }
+        }
+        Err(_) => panic!("Could not find libMXF 1.0 via pkgconfig"),
     }
 }
@@ -0,0 +1,111 @@
+extern crate mxfinfo;
+
+use mxfinfo::{MXFFile, MXFInfo, MetadataUpdate};
+use std::env;
+use std::path::Path;
+use std::process;
+
+fn usage() -> ! {
+    eprintln!("Usage: mxfinfo <file>...");
+    eprintln!("       mxfinfo extract-wav <file> <output.wav>");
+    eprintln!("       mxfinfo dump [--json] [--extensions] <file>...");
+    eprintln!(
+        "       mxfinfo update [--clip-name <name>] [--project <name>] \
+         [--comment <name>=<value>] [--attribute <name>=<value>] <file>..."
+    );
+    process::exit(2);
+}
+
+fn parse_tagged_value(arg: &str) -> Result<(String, String), String> {
+    match arg.find('=') {
+        Some(i) => Ok((arg[..i].to_string(), arg[i + 1..].to_string())),
+        None => Err(format!("Expected <name>=<value>, got {}.", arg)),
+    }
+}
+
+fn update(args: &[String]) -> Result<(), String> {
+    let mut update = MetadataUpdate::default();
+    let mut filenames = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--clip-name" => update.clip_name = Some(value()),
+            "--project" => update.project_name = Some(value()),
+            "--comment" => update.user_comments.push(parse_tagged_value(&value())?),
+            "--attribute" => update.mob_attributes.push(parse_tagged_value(&value())?),
+            _ => filenames.push(arg),
+        }
+    }
+    if filenames.is_empty() || update.is_empty() {
+        usage();
+    }
+
+    for filename in filenames {
+        MXFInfo::update_metadata(Path::new(filename), &update)
+            .map_err(|e| format!("{}: {}", filename, e))?;
+    }
+
+    Ok(())
+}
+
+fn dump(args: &[String]) -> Result<(), String> {
+    let json = args.iter().any(|arg| arg == "--json");
+    let extensions = args.iter().any(|arg| arg == "--extensions");
+    let filenames: Vec<&String> = args
+        .iter()
+        .filter(|arg| *arg != "--json" && *arg != "--extensions")
+        .collect();
+    if filenames.is_empty() {
+        usage();
+    }
+
+    for filename in filenames {
+        /* The meta-dictionary describes the application's extension sets, which are otherwise
+         * left out as dark */
+        let file = if extensions {
+            MXFFile::open_with_extensions(Path::new(filename), false)
+        } else {
+            MXFFile::open(Path::new(filename), false)
+        };
+        let dump = file.map_err(|e| format!("{}: {}", filename, e))?.dump();
+        if json {
+            println!("{}", dump.to_json());
+        } else {
+            print!("{}", dump);
+        }
+    }
+
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    match args.first().map(|arg| arg.as_str()) {
+        Some("extract-wav") => {
+            if args.len() != 3 {
+                usage();
+            }
+            MXFInfo::extract_wav(Path::new(&args[1]), Path::new(&args[2]))?;
+        }
+        Some("update") => update(&args[1..])?,
+        Some("dump") => dump(&args[1..])?,
+        Some(_) => {
+            for filename in args {
+                let info = MXFInfo::from_file(Path::new(filename))
+                    .map_err(|e| format!("{}: {}", filename, e))?;
+                println!("{}: {:#?}", filename, info);
+            }
+        }
+        None => usage(),
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("mxfinfo: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::ffi::consts::*;
+use crate::ffi::mxf::*;
+use chrono::NaiveDate;
+use std::path::Path;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InfaxRecord {
+    pub format: Option<String>,
+    pub programme_title: Option<String>,
+    pub episode_title: Option<String>,
+    pub transmission_date: Option<NaiveDate>,
+    pub magazine_prefix: Option<String>,
+    pub programme_number: Option<String>,
+    pub production_code: Option<String>,
+    pub spool_status: Option<String>,
+    pub stock_date: Option<NaiveDate>,
+    pub spool_descriptor: Option<String>,
+    pub memo: Option<String>,
+    pub duration: Option<i64>,
+    pub spool_number: Option<String>,
+    pub accession_number: Option<String>,
+    pub catalogue_detail: Option<String>,
+    pub item_number: Option<u32>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PSEFailure {
+    pub position: i64,
+    pub red_flash: i16,
+    pub spatial_pattern: i16,
+    pub luminance_flash: i16,
+    pub extended_failure: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VTRError {
+    pub position: i64,
+    pub error_code: u8,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DigiBetaDropout {
+    pub position: i64,
+    pub strength: i32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TimecodeBreak {
+    pub position: i64,
+    pub timecode_type: u16,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ArchiveMetadata {
+    /* Infax record of the source tape */
+    pub infax: Option<InfaxRecord>,
+
+    /* Per-frame QC results */
+    pub pse_failures: Vec<PSEFailure>,
+    pub vtr_errors: Vec<VTRError>,
+    pub digibeta_dropouts: Vec<DigiBetaDropout>,
+    pub timecode_breaks: Vec<TimecodeBreak>,
+
+    /* Totals recorded in the preface */
+    pub pse_failure_count: Option<u32>,
+    pub vtr_error_count: Option<u32>,
+    pub digibeta_dropout_count: Option<u32>,
+    pub timecode_break_count: Option<u32>,
+}
+
+impl ArchiveMetadata {
+    pub fn from_file(filename: &Path) -> Result<ArchiveMetadata, String> {
+        let mut archive = ArchiveMetadata::default();
+
+        /* Open file, archive preservation files are OP-1a. They are written with an open header
+         * and the QC results only go into the complete metadata in the footer. */
+        let file = MXFFile::open_with_metadata(filename, false, MetadataSource::Complete)?;
+
+        /* Get the counts from the preface */
+        let preface_set = file
+            .headerdata()
+            .find_singular_set_by_key(&G_PREFACE_SET_KEY)?;
+        if preface_set.has_item(&G_PREFACE_APP_PSEFAILURECOUNT_ITEM_KEY) {
+            archive.pse_failure_count =
+                preface_set.get_uint32(&G_PREFACE_APP_PSEFAILURECOUNT_ITEM_KEY);
+        }
+        if preface_set.has_item(&G_PREFACE_APP_VTRERRORCOUNT_ITEM_KEY) {
+            archive.vtr_error_count = preface_set.get_uint32(&G_PREFACE_APP_VTRERRORCOUNT_ITEM_KEY);
+        }
+        if preface_set.has_item(&G_PREFACE_APP_DIGIBETADROPOUTCOUNT_ITEM_KEY) {
+            archive.digibeta_dropout_count =
+                preface_set.get_uint32(&G_PREFACE_APP_DIGIBETADROPOUTCOUNT_ITEM_KEY);
+        }
+        if preface_set.has_item(&G_PREFACE_APP_TIMECODEBREAKCOUNT_ITEM_KEY) {
+            archive.timecode_break_count =
+                preface_set.get_uint32(&G_PREFACE_APP_TIMECODEBREAKCOUNT_ITEM_KEY);
+        }
+
+        /* The frameworks are referenced by DM segments, which also carry the frame position */
+        let mut list = match file.headerdata().find_set_by_key(&G_DMSEGMENT_SET_KEY) {
+            Ok(x) => x,
+            /* Not an archive preservation file */
+            Err(_) => return Ok(archive),
+        };
+        let list_iter = list.get_iter();
+        for elem in list_iter {
+            let segment_set = <&mut MXFMetadataSet>::from(elem);
+            if !segment_set.has_item(&G_DMSEGMENT_DMFRAMEWORK_ITEM_KEY) {
+                continue;
+            }
+            let framework_set = match segment_set.get_strongref(&G_DMSEGMENT_DMFRAMEWORK_ITEM_KEY) {
+                Some(x) => x,
+                /* Dark set not registered in dictionary. */
+                None => continue,
+            };
+            let position = segment_set
+                .get_position(&G_DMSEGMENT_EVENTSTARTPOSITION_ITEM_KEY)
+                .unwrap_or(0);
+
+            if framework_set.is_subclass_of(&G_APP_INFAXFRAMEWORK_SET_KEY) {
+                if archive.infax.is_none() {
+                    archive.infax = Some(read_infax_record(framework_set));
+                }
+            } else if framework_set.is_subclass_of(&G_APP_PSEANALYSISFRAMEWORK_SET_KEY) {
+                archive.pse_failures.push(PSEFailure {
+                    position: position,
+                    red_flash: framework_set
+                        .get_int16(&G_APP_PSEANALYSISFRAMEWORK_APP_REDFLASH_ITEM_KEY)
+                        .unwrap_or(0),
+                    spatial_pattern: framework_set
+                        .get_int16(&G_APP_PSEANALYSISFRAMEWORK_APP_SPATIALPATTERN_ITEM_KEY)
+                        .unwrap_or(0),
+                    luminance_flash: framework_set
+                        .get_int16(&G_APP_PSEANALYSISFRAMEWORK_APP_LUMINANCEFLASH_ITEM_KEY)
+                        .unwrap_or(0),
+                    extended_failure: framework_set
+                        .get_boolean(&G_APP_PSEANALYSISFRAMEWORK_APP_EXTENDEDFAILURE_ITEM_KEY)
+                        .unwrap_or(false),
+                });
+            } else if framework_set.is_subclass_of(&G_APP_VTRREPLAYERRORFRAMEWORK_SET_KEY) {
+                archive.vtr_errors.push(VTRError {
+                    position: position,
+                    error_code: framework_set
+                        .get_uint8(&G_APP_VTRREPLAYERRORFRAMEWORK_APP_VTRERRORCODE_ITEM_KEY)
+                        .unwrap_or(0),
+                });
+            } else if framework_set.is_subclass_of(&G_APP_DIGIBETADROPOUTFRAMEWORK_SET_KEY) {
+                archive.digibeta_dropouts.push(DigiBetaDropout {
+                    position: position,
+                    strength: framework_set
+                        .get_int32(&G_APP_DIGIBETADROPOUTFRAMEWORK_APP_STRENGTH_ITEM_KEY)
+                        .unwrap_or(0),
+                });
+            } else if framework_set.is_subclass_of(&G_APP_TIMECODEBREAKFRAMEWORK_SET_KEY) {
+                archive.timecode_breaks.push(TimecodeBreak {
+                    position: position,
+                    timecode_type: framework_set
+                        .get_uint16(&G_APP_TIMECODEBREAKFRAMEWORK_APP_TIMECODETYPE_ITEM_KEY)
+                        .unwrap_or(0),
+                });
+            }
+        }
+        MXFList::free(&mut list);
+
+        archive.pse_failures.sort_by_key(|failure| failure.position);
+        archive.vtr_errors.sort_by_key(|error| error.position);
+        archive
+            .digibeta_dropouts
+            .sort_by_key(|dropout| dropout.position);
+        archive
+            .timecode_breaks
+            .sort_by_key(|timecode_break| timecode_break.position);
+
+        Ok(archive)
+    }
+
+    pub fn is_archive_file(&self) -> bool {
+        self.infax.is_some()
+    }
+}
+
+fn read_infax_record(set: &mut MXFMetadataSet) -> InfaxRecord {
+    InfaxRecord {
+        format: set.get_string(&G_APP_INFAXFRAMEWORK_APP_FORMAT_ITEM_KEY),
+        programme_title: set.get_string(&G_APP_INFAXFRAMEWORK_APP_PROGRAMMETITLE_ITEM_KEY),
+        episode_title: set.get_string(&G_APP_INFAXFRAMEWORK_APP_EPISODETITLE_ITEM_KEY),
+        transmission_date: set
+            .get_timestamp(&G_APP_INFAXFRAMEWORK_APP_TRANSMISSIONDATE_ITEM_KEY)
+            .map(|timestamp| timestamp.date()),
+        magazine_prefix: set.get_string(&G_APP_INFAXFRAMEWORK_APP_MAGAZINEPREFIX_ITEM_KEY),
+        programme_number: set.get_string(&G_APP_INFAXFRAMEWORK_APP_PROGRAMMENUMBER_ITEM_KEY),
+        production_code: set.get_string(&G_APP_INFAXFRAMEWORK_APP_PRODUCTIONCODE_ITEM_KEY),
+        spool_status: set.get_string(&G_APP_INFAXFRAMEWORK_APP_SPOOLSTATUS_ITEM_KEY),
+        stock_date: set
+            .get_timestamp(&G_APP_INFAXFRAMEWORK_APP_STOCKDATE_ITEM_KEY)
+            .map(|timestamp| timestamp.date()),
+        spool_descriptor: set.get_string(&G_APP_INFAXFRAMEWORK_APP_SPOOLDESCRIPTOR_ITEM_KEY),
+        memo: set.get_string(&G_APP_INFAXFRAMEWORK_APP_MEMO_ITEM_KEY),
+        duration: set.get_int64(&G_APP_INFAXFRAMEWORK_APP_DURATION_ITEM_KEY),
+        spool_number: set.get_string(&G_APP_INFAXFRAMEWORK_APP_SPOOLNUMBER_ITEM_KEY),
+        accession_number: set.get_string(&G_APP_INFAXFRAMEWORK_APP_ACCESSIONNUMBER_ITEM_KEY),
+        catalogue_detail: set.get_string(&G_APP_INFAXFRAMEWORK_APP_CATALOGUEDETAIL_ITEM_KEY),
+        item_number: set.get_uint32(&G_APP_INFAXFRAMEWORK_APP_ITEMNUMBER_ITEM_KEY),
+    }
+}
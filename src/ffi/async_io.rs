@@ -0,0 +1,93 @@
+use crate::ffi::mxf::*;
+use crate::ffi::AvidMXFInfo;
+use futures::stream::{self, Stream, StreamExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs::{read_dir, File};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::task::spawn_blocking;
+
+/* Reads the bytes from the start of the file to the end of the header metadata */
+async fn read_header<R: AsyncRead + Unpin>(mut reader: R) -> Result<Vec<u8>, String> {
+    let mut data = vec![0; MXFFile::HEADER_PREFIX_SIZE];
+    reader
+        .read_exact(&mut data)
+        .await
+        .map_err(|e| format!("Could not read header: {}.", e))?;
+    let size = MXFFile::header_metadata_size(&data)? as usize;
+    if size <= data.len() {
+        data.truncate(size);
+        return Ok(data);
+    }
+    let prefix_len = data.len();
+    data.resize(size, 0);
+    reader
+        .read_exact(&mut data[prefix_len..])
+        .await
+        .map_err(|e| format!("Could not read header metadata: {}.", e))?;
+    Ok(data)
+}
+
+fn is_mxf_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| extension.eq_ignore_ascii_case("mxf"))
+}
+
+impl AvidMXFInfo {
+    /* Reads the header partition with async I/O and parses it in memory. The info is limited to
+     * what the header metadata holds; the footer partition is not read. libMXF parses
+     * synchronously, so the parse runs on the blocking pool of the tokio runtime. */
+    pub async fn from_async_reader<R: AsyncRead + Unpin>(
+        reader: R,
+        model: Arc<DataModel>,
+    ) -> Result<AvidMXFInfo, String> {
+        let data = read_header(reader).await?;
+        spawn_blocking(move || AvidMXFInfo::from_memory(data, &model))
+            .await
+            .map_err(|e| format!("Could not parse header metadata: {}.", e))?
+    }
+
+    pub async fn from_path_async<P: AsRef<Path>>(
+        path: P,
+        model: Arc<DataModel>,
+    ) -> Result<AvidMXFInfo, String> {
+        let file = File::open(path)
+            .await
+            .map_err(|e| format!("Could not read file: {}.", e))?;
+        AvidMXFInfo::from_async_reader(file, model).await
+    }
+
+    /* Reads the info of every .mxf file in a directory with at most concurrency files open at
+     * once. Files that cannot be read yield an error alongside their path without ending the
+     * stream. */
+    pub async fn scan_directory_async(
+        directory: PathBuf,
+        concurrency: usize,
+        model: Arc<DataModel>,
+    ) -> Result<impl Stream<Item = (PathBuf, Result<AvidMXFInfo, String>)>, String> {
+        let mut entries = read_dir(directory)
+            .await
+            .map_err(|e| format!("Could not read directory: {}.", e))?;
+        let mut paths = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| format!("Could not read directory: {}.", e))?
+        {
+            let path = entry.path();
+            if is_mxf_path(&path) {
+                paths.push(path);
+            }
+        }
+        Ok(stream::iter(paths)
+            .map(move |path| {
+                let model = model.clone();
+                async move {
+                    let result = AvidMXFInfo::from_path_async(&path, model).await;
+                    (path, result)
+                }
+            })
+            .buffer_unordered(concurrency.max(1)))
+    }
+}
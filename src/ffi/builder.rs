@@ -0,0 +1,999 @@
+use crate::ffi::archive::{ArchiveMetadata, InfaxRecord};
+use crate::ffi::consts::*;
+use crate::ffi::index::IndexTableSegment;
+use crate::ffi::labels::label_by_name;
+use crate::ffi::mxf::*;
+use crate::ffi::source_chain::PackageDescriptorType;
+use crate::ffi::RGBColor;
+use chrono::{NaiveDateTime, Utc};
+use num_rational::Rational32;
+use std::path::Path;
+use std::sync::Arc;
+
+const INDEX_SID: u32 = 1;
+const BODY_SID: u32 = 2;
+
+/* Length of the source clip referencing the physical package, Avid uses a large fixed value */
+const PHYSICAL_PACKAGE_DURATION: i64 = 10 * 60 * 60 * 25;
+
+fn label(name: &str) -> Result<MXFKey, String> {
+    label_by_name(name).ok_or(format!("Unknown label {}.", name))
+}
+
+/* Generic container element key of a single clip-wrapped picture or sound element */
+fn essence_element_key(is_picture: bool) -> MXFKey {
+    let item_type = if is_picture { 0x15 } else { 0x16 };
+    MXFKey::new(
+        0x06, 0x0e, 0x2b, 0x34, 0x01, 0x02, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, item_type, 0x01,
+        0x02, 0x01,
+    )
+}
+
+/* An editor marker on the event track of the material package */
+#[derive(Debug, Clone, PartialEq)]
+struct BuilderMarker {
+    position: i64,
+    comment: String,
+    color: Option<RGBColor>,
+}
+
+/* Writes small OP-Atom files with clip-wrapped essence filled with zeros. The layout follows
+ * Avid: header metadata in the closed header partition, essence in a body partition and a CBR
+ * index table segment in the footer partition. With an open header, the header partition holds
+ * the metadata as written when a capture starts and the footer partition the complete metadata.
+ * Package UIDs are generated unless set, so fixtures that compare them should set their own. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpAtomBuilder {
+    is_picture: bool,
+    edit_rate: Rational32,
+    duration: i64,
+    project_edit_rate: Rational32,
+    clip_name: Option<String>,
+    project_name: Option<String>,
+    created: NaiveDateTime,
+    material_package_uid: MXFUmid,
+    file_package_uid: MXFUmid,
+    physical_package: Option<(PackageDescriptorType, String, MXFUmid)>,
+    timecode: Option<(i64, u16, bool)>,
+    essence_group: bool,
+    mob_attributes: Vec<(String, String)>,
+    user_comments: Vec<(String, String)>,
+    stored_size: (u32, u32),
+    channel_count: u32,
+    quantization_bits: u32,
+    header_fill: u32,
+    essence_container: Option<String>,
+    picture_coding: Option<String>,
+    codec: Option<MXFKey>,
+    app_code: Option<i32>,
+    /* Further material tracks referencing the file packages of other files */
+    material_tracks: Vec<(bool, MXFUmid)>,
+    markers: Vec<BuilderMarker>,
+    open_header: bool,
+    archive: Option<ArchiveMetadata>,
+}
+
+impl OpAtomBuilder {
+    fn new(is_picture: bool, edit_rate: Rational32, duration: i64) -> OpAtomBuilder {
+        OpAtomBuilder {
+            is_picture: is_picture,
+            edit_rate: edit_rate,
+            duration: duration,
+            project_edit_rate: if is_picture {
+                edit_rate
+            } else {
+                Rational32::new(25, 1)
+            },
+            clip_name: None,
+            project_name: None,
+            created: Utc::now().naive_utc(),
+            material_package_uid: MXFUmid::generate(),
+            file_package_uid: MXFUmid::generate(),
+            physical_package: None,
+            timecode: None,
+            essence_group: false,
+            mob_attributes: Vec::new(),
+            user_comments: Vec::new(),
+            stored_size: (16, 16),
+            channel_count: 1,
+            quantization_bits: 16,
+            header_fill: 4096,
+            essence_container: None,
+            picture_coding: None,
+            codec: None,
+            app_code: None,
+            material_tracks: Vec::new(),
+            markers: Vec::new(),
+            open_header: false,
+            archive: None,
+        }
+    }
+
+    /* Uncompressed 8 bit 4:2:2 video, duration in frames */
+    pub fn picture(edit_rate: Rational32, duration: i64) -> OpAtomBuilder {
+        OpAtomBuilder::new(true, edit_rate, duration)
+    }
+
+    /* PCM audio with the sampling rate as edit rate, duration in samples */
+    pub fn sound(sampling_rate: Rational32, duration: i64) -> OpAtomBuilder {
+        OpAtomBuilder::new(false, sampling_rate, duration)
+    }
+
+    pub fn project_edit_rate(mut self, edit_rate: Rational32) -> OpAtomBuilder {
+        self.project_edit_rate = edit_rate;
+        self
+    }
+
+    pub fn clip_name(mut self, name: &str) -> OpAtomBuilder {
+        self.clip_name = Some(name.to_string());
+        self
+    }
+
+    pub fn project_name(mut self, name: &str) -> OpAtomBuilder {
+        self.project_name = Some(name.to_string());
+        self
+    }
+
+    pub fn created(mut self, created: NaiveDateTime) -> OpAtomBuilder {
+        self.created = created;
+        self
+    }
+
+    pub fn material_package_uid(mut self, uid: MXFUmid) -> OpAtomBuilder {
+        self.material_package_uid = uid;
+        self
+    }
+
+    pub fn file_package_uid(mut self, uid: MXFUmid) -> OpAtomBuilder {
+        self.file_package_uid = uid;
+        self
+    }
+
+    /* Adds a tape, import or recording source package referenced by the file package. A picture
+     * or sound source package stands for the file package of the media a transcode was made
+     * from. */
+    pub fn physical_package(
+        mut self,
+        descriptor_type: PackageDescriptorType,
+        name: &str,
+    ) -> OpAtomBuilder {
+        let uid = self
+            .physical_package
+            .take()
+            .map_or_else(MXFUmid::generate, |(_, _, uid)| uid);
+        self.physical_package = Some((descriptor_type, name.to_string(), uid));
+        self
+    }
+
+    pub fn physical_package_uid(mut self, uid: MXFUmid) -> OpAtomBuilder {
+        if let Some(ref mut package) = self.physical_package {
+            package.2 = uid;
+        }
+        self
+    }
+
+    /* Timecode track of the physical package, which is required to write one */
+    pub fn timecode(mut self, start: i64, rounded_base: u16, drop_frame: bool) -> OpAtomBuilder {
+        self.timecode = Some((start, rounded_base, drop_frame));
+        self
+    }
+
+    /* Wraps the material package source clip in an essence group with a single choice */
+    pub fn essence_group(mut self, essence_group: bool) -> OpAtomBuilder {
+        self.essence_group = essence_group;
+        self
+    }
+
+    pub fn mob_attribute(mut self, name: &str, value: &str) -> OpAtomBuilder {
+        self.mob_attributes
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn user_comment(mut self, name: &str, value: &str) -> OpAtomBuilder {
+        self.user_comments
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn stored_size(mut self, width: u32, height: u32) -> OpAtomBuilder {
+        self.stored_size = (width, height);
+        self
+    }
+
+    pub fn channel_count(mut self, channel_count: u32) -> OpAtomBuilder {
+        self.channel_count = channel_count;
+        self
+    }
+
+    pub fn quantization_bits(mut self, quantization_bits: u32) -> OpAtomBuilder {
+        self.quantization_bits = quantization_bits;
+        self
+    }
+
+    /* Fill reserved after the header metadata for updating it in place */
+    pub fn header_fill(mut self, header_fill: u32) -> OpAtomBuilder {
+        self.header_fill = header_fill;
+        self
+    }
+
+    /* Essence container label by name, e.g. "AES3 Clip-wrapped". An AES3 container is described
+     * with an AES3 audio descriptor. */
+    pub fn essence_container(mut self, name: &str) -> OpAtomBuilder {
+        self.essence_container = Some(name.to_string());
+        self
+    }
+
+    /* Picture essence coding label by name, e.g. "DNxHD 1252" */
+    pub fn picture_coding(mut self, name: &str) -> OpAtomBuilder {
+        self.picture_coding = Some(name.to_string());
+        self
+    }
+
+    /* Codec definition of the file descriptor */
+    pub fn codec(mut self, codec: MXFKey) -> OpAtomBuilder {
+        self.codec = Some(codec);
+        self
+    }
+
+    pub fn app_code(mut self, app_code: i32) -> OpAtomBuilder {
+        self.app_code = Some(app_code);
+        self
+    }
+
+    /* Adds a material track referencing the file package of another file, as Avid writes for
+     * every track of a master clip */
+    pub fn material_track(mut self, is_picture: bool) -> OpAtomBuilder {
+        self.material_tracks.push((is_picture, MXFUmid::generate()));
+        self
+    }
+
+    /* Adds an editor marker on the event track of the material package, at a position in edit
+     * units */
+    pub fn marker(
+        mut self,
+        position: i64,
+        comment: &str,
+        color: Option<RGBColor>,
+    ) -> OpAtomBuilder {
+        self.markers.push(BuilderMarker {
+            position: position,
+            comment: comment.to_string(),
+            color: color,
+        });
+        self
+    }
+
+    /* Writes an open and incomplete header partition with zero durations and no markers, and the
+     * complete metadata in the footer partition */
+    pub fn open_header(mut self, open_header: bool) -> OpAtomBuilder {
+        self.open_header = open_header;
+        self
+    }
+
+    /* Archive preservation metadata on an event track of the file package, see
+     * ArchiveMetadata::from_file. It is written with the complete metadata only, so combine it
+     * with an open header for a file like the ones written by the archive ingest. */
+    pub fn archive_metadata(mut self, archive: ArchiveMetadata) -> OpAtomBuilder {
+        self.archive = Some(archive);
+        self
+    }
+
+    fn edit_unit_byte_count(&self) -> u32 {
+        if self.is_picture {
+            self.stored_size.0 * self.stored_size.1 * 2
+        } else {
+            self.channel_count * ((self.quantization_bits + 7) / 8)
+        }
+    }
+
+    fn essence_container_name(&self) -> &str {
+        match self.essence_container {
+            Some(ref name) => name,
+            None if self.is_picture => "Uncompressed Picture Clip-wrapped",
+            None => "BWF Clip-wrapped",
+        }
+    }
+
+    fn essence_container_label(&self) -> Result<MXFKey, String> {
+        label(self.essence_container_name())
+    }
+
+    fn data_definition(is_picture: bool) -> Result<MXFKey, String> {
+        if is_picture {
+            label("Picture Essence Track")
+        } else {
+            label("Sound Essence Track")
+        }
+    }
+
+    fn write_package(
+        &self,
+        writer: &MXFWriter,
+        set_key: &MXFKey,
+        uid: &MXFUmid,
+        name: Option<&str>,
+    ) -> Result<&'static mut MXFMetadataSet, String> {
+        let package_set = writer.headerdata().create_set(set_key)?;
+        package_set.set_umid(&G_GENERICPACKAGE_PACKAGEUID_ITEM_KEY, uid)?;
+        if let Some(name) = name {
+            package_set.set_string(&G_GENERICPACKAGE_NAME_ITEM_KEY, name)?;
+        }
+        package_set.set_timestamp(
+            &G_GENERICPACKAGE_PACKAGECREATIONDATE_ITEM_KEY,
+            &self.created,
+        )?;
+        package_set.set_timestamp(
+            &G_GENERICPACKAGE_PACKAGEMODIFIEDDATE_ITEM_KEY,
+            &self.created,
+        )?;
+
+        Ok(package_set)
+    }
+
+    /* Adds a track with a sequence holding a single component, which is returned */
+    fn write_track(
+        &self,
+        package_set: &mut MXFMetadataSet,
+        track_id: u32,
+        track_number: u32,
+        data_definition: &MXFKey,
+        duration: i64,
+        component_key: &MXFKey,
+    ) -> Result<&'static mut MXFMetadataSet, String> {
+        let track_set = package_set.headerdata().create_set(&G_TRACK_SET_KEY)?;
+        package_set.add_strongref(&G_GENERICPACKAGE_TRACKS_ITEM_KEY, track_set)?;
+        track_set.set_uint32(&G_GENERICTRACK_TRACKID_ITEM_KEY, track_id)?;
+        track_set.set_uint32(&G_GENERICTRACK_TRACKNUMBER_ITEM_KEY, track_number)?;
+        track_set.set_rational(&G_TRACK_EDITRATE_ITEM_KEY, &self.edit_rate)?;
+        track_set.set_position(&G_TRACK_ORIGIN_ITEM_KEY, 0)?;
+
+        let sequence_set = package_set.headerdata().create_set(&G_SEQUENCE_SET_KEY)?;
+        track_set.set_strongref(&G_GENERICTRACK_SEQUENCE_ITEM_KEY, sequence_set)?;
+        sequence_set.set_ul(
+            &G_STRUCTURALCOMPONENT_DATADEFINITION_ITEM_KEY,
+            data_definition,
+        )?;
+        sequence_set.set_length(&G_STRUCTURALCOMPONENT_DURATION_ITEM_KEY, duration)?;
+
+        let component_set = package_set.headerdata().create_set(component_key)?;
+        sequence_set.add_strongref(&G_SEQUENCE_STRUCTURALCOMPONENTS_ITEM_KEY, component_set)?;
+        component_set.set_ul(
+            &G_STRUCTURALCOMPONENT_DATADEFINITION_ITEM_KEY,
+            data_definition,
+        )?;
+        component_set.set_length(&G_STRUCTURALCOMPONENT_DURATION_ITEM_KEY, duration)?;
+
+        Ok(component_set)
+    }
+
+    fn set_source_clip(
+        source_clip_set: &mut MXFMetadataSet,
+        package_uid: &MXFUmid,
+        track_id: u32,
+    ) -> Result<(), String> {
+        source_clip_set.set_position(&G_SOURCECLIP_STARTPOSITION_ITEM_KEY, 0)?;
+        source_clip_set.set_umid(&G_SOURCECLIP_SOURCEPACKAGEID_ITEM_KEY, package_uid)?;
+        source_clip_set.set_uint32(&G_SOURCECLIP_SOURCETRACKID_ITEM_KEY, track_id)
+    }
+
+    fn write_descriptor(
+        &self,
+        writer: &MXFWriter,
+        duration: i64,
+    ) -> Result<&'static mut MXFMetadataSet, String> {
+        let descriptor_set = if self.is_picture {
+            let set = writer
+                .headerdata()
+                .create_set(&G_CDCIESSENCEDESCRIPTOR_SET_KEY)?;
+            let (width, height) = self.stored_size;
+            set.set_uint8(&G_GENERICPICTUREESSENCEDESCRIPTOR_FRAMELAYOUT_ITEM_KEY, 0)?;
+            set.set_uint32(
+                &G_GENERICPICTUREESSENCEDESCRIPTOR_STOREDWIDTH_ITEM_KEY,
+                width,
+            )?;
+            set.set_uint32(
+                &G_GENERICPICTUREESSENCEDESCRIPTOR_STOREDHEIGHT_ITEM_KEY,
+                height,
+            )?;
+            set.set_rational(
+                &G_GENERICPICTUREESSENCEDESCRIPTOR_ASPECTRATIO_ITEM_KEY,
+                &Rational32::new(width as i32, height as i32),
+            )?;
+            set.set_ul(
+                &G_GENERICPICTUREESSENCEDESCRIPTOR_PICTUREESSENCECODING_ITEM_KEY,
+                &label(
+                    self.picture_coding
+                        .as_ref()
+                        .map_or("Uncompressed Picture", |name| name.as_str()),
+                )?,
+            )?;
+            set.set_uint32(&G_CDCIESSENCEDESCRIPTOR_COMPONENTDEPTH_ITEM_KEY, 8)?;
+            set.set_uint32(&G_CDCIESSENCEDESCRIPTOR_HORIZONTALSUBSAMPLING_ITEM_KEY, 2)?;
+            set
+        } else {
+            let descriptor_key = if self.essence_container_name().starts_with("AES3") {
+                G_AES3AUDIODESCRIPTOR_SET_KEY
+            } else {
+                G_WAVEAUDIODESCRIPTOR_SET_KEY
+            };
+            let set = writer.headerdata().create_set(&descriptor_key)?;
+            let block_align = self.edit_unit_byte_count();
+            set.set_rational(
+                &G_GENERICSOUNDESSENCEDESCRIPTOR_AUDIOSAMPLINGRATE_ITEM_KEY,
+                &self.edit_rate,
+            )?;
+            set.set_boolean(&G_GENERICSOUNDESSENCEDESCRIPTOR_LOCKED_ITEM_KEY, true)?;
+            set.set_uint32(
+                &G_GENERICSOUNDESSENCEDESCRIPTOR_CHANNELCOUNT_ITEM_KEY,
+                self.channel_count,
+            )?;
+            set.set_uint32(
+                &G_GENERICSOUNDESSENCEDESCRIPTOR_QUANTIZATIONBITS_ITEM_KEY,
+                self.quantization_bits,
+            )?;
+            set.set_uint16(
+                &G_WAVEAUDIODESCRIPTOR_BLOCKALIGN_ITEM_KEY,
+                block_align as u16,
+            )?;
+            set.set_uint32(
+                &G_WAVEAUDIODESCRIPTOR_AVGBPS_ITEM_KEY,
+                block_align * (*self.edit_rate.numer() / *self.edit_rate.denom()) as u32,
+            )?;
+            set
+        };
+        descriptor_set.set_rational(&G_FILEDESCRIPTOR_SAMPLERATE_ITEM_KEY, &self.edit_rate)?;
+        descriptor_set.set_length(&G_FILEDESCRIPTOR_CONTAINERDURATION_ITEM_KEY, duration)?;
+        descriptor_set.set_ul(
+            &G_FILEDESCRIPTOR_ESSENCECONTAINER_ITEM_KEY,
+            &self.essence_container_label()?,
+        )?;
+        descriptor_set.set_uint32(&G_FILEDESCRIPTOR_LINKEDTRACKID_ITEM_KEY, 1)?;
+        if let Some(ref codec) = self.codec {
+            descriptor_set.set_ul(&G_FILEDESCRIPTOR_CODEC_ITEM_KEY, codec)?;
+        }
+
+        Ok(descriptor_set)
+    }
+
+    fn write_physical_package(
+        &self,
+        writer: &MXFWriter,
+        content_storage_set: &mut MXFMetadataSet,
+    ) -> Result<(), String> {
+        let (descriptor_type, ref name, ref uid) = match self.physical_package {
+            Some(ref package) => package,
+            None => return Ok(()),
+        };
+        let descriptor_key = match *descriptor_type {
+            PackageDescriptorType::Tape => G_TAPEDESCRIPTOR_SET_KEY,
+            PackageDescriptorType::Import => G_IMPORTDESCRIPTOR_SET_KEY,
+            PackageDescriptorType::Recording => G_RECORDINGDESCRIPTOR_SET_KEY,
+            PackageDescriptorType::Picture => G_CDCIESSENCEDESCRIPTOR_SET_KEY,
+            PackageDescriptorType::Sound => G_WAVEAUDIODESCRIPTOR_SET_KEY,
+            other => return Err(format!("Unsupported physical package type {}.", other)),
+        };
+
+        let package_set = self.write_package(writer, &G_SOURCEPACKAGE_SET_KEY, uid, Some(name))?;
+        content_storage_set.add_strongref(&G_CONTENTSTORAGE_PACKAGES_ITEM_KEY, package_set)?;
+        let descriptor_set = writer.headerdata().create_set(&descriptor_key)?;
+        package_set.set_strongref(&G_SOURCEPACKAGE_DESCRIPTOR_ITEM_KEY, descriptor_set)?;
+        if *descriptor_type == PackageDescriptorType::Picture
+            || *descriptor_type == PackageDescriptorType::Sound
+        {
+            descriptor_set.set_rational(&G_FILEDESCRIPTOR_SAMPLERATE_ITEM_KEY, &self.edit_rate)?;
+            descriptor_set.set_ul(
+                &G_FILEDESCRIPTOR_ESSENCECONTAINER_ITEM_KEY,
+                &self.essence_container_label()?,
+            )?;
+        }
+
+        let source_clip_set = self.write_track(
+            package_set,
+            1,
+            1,
+            &OpAtomBuilder::data_definition(self.is_picture)?,
+            PHYSICAL_PACKAGE_DURATION,
+            &G_SOURCECLIP_SET_KEY,
+        )?;
+        OpAtomBuilder::set_source_clip(source_clip_set, &MXFUmid::default(), 0)?;
+
+        if let Some((start, rounded_base, drop_frame)) = self.timecode {
+            let timecode_set = self.write_track(
+                package_set,
+                2,
+                1,
+                &label("SMPTE 12M Timecode Track")?,
+                PHYSICAL_PACKAGE_DURATION,
+                &G_TIMECODECOMPONENT_SET_KEY,
+            )?;
+            timecode_set.set_position(&G_TIMECODECOMPONENT_STARTTIMECODE_ITEM_KEY, start)?;
+            timecode_set.set_uint16(
+                &G_TIMECODECOMPONENT_ROUNDEDTIMECODEBASE_ITEM_KEY,
+                rounded_base,
+            )?;
+            timecode_set.set_boolean(&G_TIMECODECOMPONENT_DROPFRAME_ITEM_KEY, drop_frame)?;
+        }
+
+        Ok(())
+    }
+
+    /* Event track with an empty sequence for DM segments */
+    fn write_event_track(
+        &self,
+        package_set: &mut MXFMetadataSet,
+        track_id: u32,
+    ) -> Result<&'static mut MXFMetadataSet, String> {
+        let data_definition = label("Descriptive Metadata Track")?;
+        let track_set = package_set.headerdata().create_set(&G_EVENTTRACK_SET_KEY)?;
+        package_set.add_strongref(&G_GENERICPACKAGE_TRACKS_ITEM_KEY, track_set)?;
+        track_set.set_uint32(&G_GENERICTRACK_TRACKID_ITEM_KEY, track_id)?;
+        track_set.set_uint32(&G_GENERICTRACK_TRACKNUMBER_ITEM_KEY, 1)?;
+        track_set.set_rational(&G_EVENTTRACK_EVENTEDITRATE_ITEM_KEY, &self.edit_rate)?;
+        track_set.set_position(&G_EVENTTRACK_EVENTORIGIN_ITEM_KEY, 0)?;
+
+        let sequence_set = package_set.headerdata().create_set(&G_SEQUENCE_SET_KEY)?;
+        track_set.set_strongref(&G_GENERICTRACK_SEQUENCE_ITEM_KEY, sequence_set)?;
+        sequence_set.set_ul(
+            &G_STRUCTURALCOMPONENT_DATADEFINITION_ITEM_KEY,
+            &data_definition,
+        )?;
+
+        Ok(sequence_set)
+    }
+
+    fn write_dm_segment(
+        sequence_set: &mut MXFMetadataSet,
+        position: i64,
+    ) -> Result<&'static mut MXFMetadataSet, String> {
+        let segment_set = sequence_set.headerdata().create_set(&G_DMSEGMENT_SET_KEY)?;
+        sequence_set.add_strongref(&G_SEQUENCE_STRUCTURALCOMPONENTS_ITEM_KEY, segment_set)?;
+        segment_set.set_ul(
+            &G_STRUCTURALCOMPONENT_DATADEFINITION_ITEM_KEY,
+            &label("Descriptive Metadata Track")?,
+        )?;
+        segment_set.set_position(&G_DMSEGMENT_EVENTSTARTPOSITION_ITEM_KEY, position)?;
+
+        Ok(segment_set)
+    }
+
+    /* DM segment referencing a new framework set */
+    fn write_framework(
+        sequence_set: &mut MXFMetadataSet,
+        position: i64,
+        framework_key: &MXFKey,
+    ) -> Result<&'static mut MXFMetadataSet, String> {
+        let segment_set = OpAtomBuilder::write_dm_segment(sequence_set, position)?;
+        let framework_set = sequence_set.headerdata().create_set(framework_key)?;
+        segment_set.set_strongref(&G_DMSEGMENT_DMFRAMEWORK_ITEM_KEY, framework_set)?;
+
+        Ok(framework_set)
+    }
+
+    /* Event track with a DM segment for each marker, see AvidMXFInfo::markers */
+    fn write_markers(&self, package_set: &mut MXFMetadataSet, track_id: u32) -> Result<(), String> {
+        let sequence_set = self.write_event_track(package_set, track_id)?;
+        for marker in &self.markers {
+            let segment_set = OpAtomBuilder::write_dm_segment(sequence_set, marker.position)?;
+            sequence_set.add_strongref(&G_SEQUENCE_STRUCTURALCOMPONENTS_ITEM_KEY, segment_set)?;
+            segment_set.set_string(&G_DMSEGMENT_EVENTCOMMENT_ITEM_KEY, &marker.comment)?;
+            segment_set.set_uint32_array(&G_DMSEGMENT_TRACKIDS_ITEM_KEY, &[1])?;
+            /* Avid RGBColor record of three UInt16 values */
+            if let Some(color) = marker.color {
+                let mut value = Vec::with_capacity(6);
+                for component in &[color.red, color.green, color.blue] {
+                    value.extend_from_slice(&component.to_be_bytes());
+                }
+                segment_set.set_item_value(&G_DMSEGMENT_COMMENTMARKERCOLOR_ITEM_KEY, &value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_infax_record(set: &mut MXFMetadataSet, infax: &InfaxRecord) -> Result<(), String> {
+        let strings = [
+            (&G_APP_INFAXFRAMEWORK_APP_FORMAT_ITEM_KEY, &infax.format),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_PROGRAMMETITLE_ITEM_KEY,
+                &infax.programme_title,
+            ),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_EPISODETITLE_ITEM_KEY,
+                &infax.episode_title,
+            ),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_MAGAZINEPREFIX_ITEM_KEY,
+                &infax.magazine_prefix,
+            ),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_PROGRAMMENUMBER_ITEM_KEY,
+                &infax.programme_number,
+            ),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_PRODUCTIONCODE_ITEM_KEY,
+                &infax.production_code,
+            ),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_SPOOLSTATUS_ITEM_KEY,
+                &infax.spool_status,
+            ),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_SPOOLDESCRIPTOR_ITEM_KEY,
+                &infax.spool_descriptor,
+            ),
+            (&G_APP_INFAXFRAMEWORK_APP_MEMO_ITEM_KEY, &infax.memo),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_SPOOLNUMBER_ITEM_KEY,
+                &infax.spool_number,
+            ),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_ACCESSIONNUMBER_ITEM_KEY,
+                &infax.accession_number,
+            ),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_CATALOGUEDETAIL_ITEM_KEY,
+                &infax.catalogue_detail,
+            ),
+        ];
+        for &(key, value) in strings.iter() {
+            if let Some(ref value) = *value {
+                set.set_string(key, value)?;
+            }
+        }
+        /* Dates are timestamps at midnight */
+        let dates = [
+            (
+                &G_APP_INFAXFRAMEWORK_APP_TRANSMISSIONDATE_ITEM_KEY,
+                &infax.transmission_date,
+            ),
+            (
+                &G_APP_INFAXFRAMEWORK_APP_STOCKDATE_ITEM_KEY,
+                &infax.stock_date,
+            ),
+        ];
+        for &(key, value) in dates.iter() {
+            if let Some(ref value) = *value {
+                set.set_timestamp(key, &value.and_hms(0, 0, 0))?;
+            }
+        }
+        if let Some(duration) = infax.duration {
+            set.set_int64(&G_APP_INFAXFRAMEWORK_APP_DURATION_ITEM_KEY, duration)?;
+        }
+        if let Some(item_number) = infax.item_number {
+            set.set_uint32(&G_APP_INFAXFRAMEWORK_APP_ITEMNUMBER_ITEM_KEY, item_number)?;
+        }
+
+        Ok(())
+    }
+
+    /* The Infax record and the QC results as DM segments on an event track of the file package,
+     * with the totals in the preface */
+    fn write_archive_metadata(
+        archive: &ArchiveMetadata,
+        preface_set: &mut MXFMetadataSet,
+        sequence_set: &mut MXFMetadataSet,
+    ) -> Result<(), String> {
+        let counts = [
+            (
+                &G_PREFACE_APP_PSEFAILURECOUNT_ITEM_KEY,
+                archive.pse_failure_count,
+            ),
+            (
+                &G_PREFACE_APP_VTRERRORCOUNT_ITEM_KEY,
+                archive.vtr_error_count,
+            ),
+            (
+                &G_PREFACE_APP_DIGIBETADROPOUTCOUNT_ITEM_KEY,
+                archive.digibeta_dropout_count,
+            ),
+            (
+                &G_PREFACE_APP_TIMECODEBREAKCOUNT_ITEM_KEY,
+                archive.timecode_break_count,
+            ),
+        ];
+        for &(key, count) in counts.iter() {
+            if let Some(count) = count {
+                preface_set.set_uint32(key, count)?;
+            }
+        }
+
+        if let Some(ref infax) = archive.infax {
+            let set =
+                OpAtomBuilder::write_framework(sequence_set, 0, &G_APP_INFAXFRAMEWORK_SET_KEY)?;
+            OpAtomBuilder::write_infax_record(set, infax)?;
+        }
+        for failure in &archive.pse_failures {
+            let set = OpAtomBuilder::write_framework(
+                sequence_set,
+                failure.position,
+                &G_APP_PSEANALYSISFRAMEWORK_SET_KEY,
+            )?;
+            set.set_int16(
+                &G_APP_PSEANALYSISFRAMEWORK_APP_REDFLASH_ITEM_KEY,
+                failure.red_flash,
+            )?;
+            set.set_int16(
+                &G_APP_PSEANALYSISFRAMEWORK_APP_SPATIALPATTERN_ITEM_KEY,
+                failure.spatial_pattern,
+            )?;
+            set.set_int16(
+                &G_APP_PSEANALYSISFRAMEWORK_APP_LUMINANCEFLASH_ITEM_KEY,
+                failure.luminance_flash,
+            )?;
+            set.set_boolean(
+                &G_APP_PSEANALYSISFRAMEWORK_APP_EXTENDEDFAILURE_ITEM_KEY,
+                failure.extended_failure,
+            )?;
+        }
+        for error in &archive.vtr_errors {
+            let set = OpAtomBuilder::write_framework(
+                sequence_set,
+                error.position,
+                &G_APP_VTRREPLAYERRORFRAMEWORK_SET_KEY,
+            )?;
+            set.set_uint8(
+                &G_APP_VTRREPLAYERRORFRAMEWORK_APP_VTRERRORCODE_ITEM_KEY,
+                error.error_code,
+            )?;
+        }
+        for dropout in &archive.digibeta_dropouts {
+            let set = OpAtomBuilder::write_framework(
+                sequence_set,
+                dropout.position,
+                &G_APP_DIGIBETADROPOUTFRAMEWORK_SET_KEY,
+            )?;
+            set.set_int32(
+                &G_APP_DIGIBETADROPOUTFRAMEWORK_APP_STRENGTH_ITEM_KEY,
+                dropout.strength,
+            )?;
+        }
+        for timecode_break in &archive.timecode_breaks {
+            let set = OpAtomBuilder::write_framework(
+                sequence_set,
+                timecode_break.position,
+                &G_APP_TIMECODEBREAKFRAMEWORK_SET_KEY,
+            )?;
+            set.set_uint16(
+                &G_APP_TIMECODEBREAKFRAMEWORK_APP_TIMECODETYPE_ITEM_KEY,
+                timecode_break.timecode_type,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /* The metadata of an open header has zero durations and no markers, as written when a
+     * capture starts */
+    fn write_header_metadata(&self, writer: &MXFWriter, is_complete: bool) -> Result<(), String> {
+        let essence_container = self.essence_container_label()?;
+        let data_definition = OpAtomBuilder::data_definition(self.is_picture)?;
+        let duration = if is_complete { self.duration } else { 0 };
+
+        let preface_set = writer.headerdata().create_set(&G_PREFACE_SET_KEY)?;
+        preface_set.set_timestamp(&G_PREFACE_LASTMODIFIEDDATE_ITEM_KEY, &self.created)?;
+        preface_set.set_uint16(&G_PREFACE_VERSION_ITEM_KEY, 0x0102)?;
+        preface_set.set_uint32(&G_PREFACE_OBJECTMODELVERSION_ITEM_KEY, 1)?;
+        preface_set.set_ul(
+            &G_PREFACE_OPERATIONALPATTERN_ITEM_KEY,
+            &label("OP-Atom 1 Track 1 SourceClip")?,
+        )?;
+        preface_set.set_ul_array(&G_PREFACE_ESSENCECONTAINERS_ITEM_KEY, &[essence_container])?;
+        preface_set.set_ul_array(&G_PREFACE_DMSCHEMES_ITEM_KEY, &[])?;
+        preface_set.set_rational(&G_PREFACE_PROJECTEDITRATE_ITEM_KEY, &self.project_edit_rate)?;
+        if let Some(ref project_name) = self.project_name {
+            preface_set.set_string(&G_PREFACE_PROJECTNAME_ITEM_KEY, project_name)?;
+        }
+
+        let identification_set = writer.headerdata().create_set(&G_IDENTIFICATION_SET_KEY)?;
+        preface_set.add_strongref(&G_PREFACE_IDENTIFICATIONS_ITEM_KEY, identification_set)?;
+        identification_set.set_uuid(
+            &G_IDENTIFICATION_THISGENERATIONUID_ITEM_KEY,
+            &MXFKey::generate_uuid(),
+        )?;
+        identification_set.set_string(&G_IDENTIFICATION_COMPANYNAME_ITEM_KEY, "mxfinfo")?;
+        identification_set.set_string(&G_IDENTIFICATION_PRODUCTNAME_ITEM_KEY, "OpAtomBuilder")?;
+        identification_set.set_string(
+            &G_IDENTIFICATION_VERSIONSTRING_ITEM_KEY,
+            env!("CARGO_PKG_VERSION"),
+        )?;
+        identification_set.set_uuid(
+            &G_IDENTIFICATION_PRODUCTUID_ITEM_KEY,
+            &MXFKey::generate_uuid(),
+        )?;
+        identification_set
+            .set_timestamp(&G_IDENTIFICATION_MODIFICATIONDATE_ITEM_KEY, &self.created)?;
+
+        let content_storage_set = writer.headerdata().create_set(&G_CONTENTSTORAGE_SET_KEY)?;
+        preface_set.set_strongref(&G_PREFACE_CONTENTSTORAGE_ITEM_KEY, content_storage_set)?;
+
+        let essence_container_data_set = writer
+            .headerdata()
+            .create_set(&G_ESSENCECONTAINERDATA_SET_KEY)?;
+        content_storage_set.add_strongref(
+            &G_CONTENTSTORAGE_ESSENCECONTAINERDATA_ITEM_KEY,
+            essence_container_data_set,
+        )?;
+        essence_container_data_set.set_umid(
+            &G_ESSENCECONTAINERDATA_LINKEDPACKAGEUID_ITEM_KEY,
+            &self.file_package_uid,
+        )?;
+        essence_container_data_set
+            .set_uint32(&G_ESSENCECONTAINERDATA_INDEXSID_ITEM_KEY, INDEX_SID)?;
+        essence_container_data_set
+            .set_uint32(&G_ESSENCECONTAINERDATA_BODYSID_ITEM_KEY, BODY_SID)?;
+
+        /* Material package */
+        let package_set = self.write_package(
+            writer,
+            &G_MATERIALPACKAGE_SET_KEY,
+            &self.material_package_uid,
+            self.clip_name.as_ref().map(|name| name.as_str()),
+        )?;
+        content_storage_set.add_strongref(&G_CONTENTSTORAGE_PACKAGES_ITEM_KEY, package_set)?;
+        let component_key = if self.essence_group {
+            G_ESSENCEGROUP_SET_KEY
+        } else {
+            G_SOURCECLIP_SET_KEY
+        };
+        let component_set = self.write_track(
+            package_set,
+            1,
+            1,
+            &data_definition,
+            duration,
+            &component_key,
+        )?;
+        if self.essence_group {
+            let source_clip_set = writer.headerdata().create_set(&G_SOURCECLIP_SET_KEY)?;
+            component_set.add_strongref(&G_ESSENCEGROUP_CHOICES_ITEM_KEY, source_clip_set)?;
+            source_clip_set.set_ul(
+                &G_STRUCTURALCOMPONENT_DATADEFINITION_ITEM_KEY,
+                &data_definition,
+            )?;
+            source_clip_set.set_length(&G_STRUCTURALCOMPONENT_DURATION_ITEM_KEY, duration)?;
+            OpAtomBuilder::set_source_clip(source_clip_set, &self.file_package_uid, 1)?;
+        } else {
+            OpAtomBuilder::set_source_clip(component_set, &self.file_package_uid, 1)?;
+        }
+
+        /* Track numbers count the tracks of each kind */
+        let mut track_numbers = if self.is_picture { (1, 0) } else { (0, 1) };
+        for (i, &(is_picture, ref uid)) in self.material_tracks.iter().enumerate() {
+            let track_number = if is_picture {
+                track_numbers.0 += 1;
+                track_numbers.0
+            } else {
+                track_numbers.1 += 1;
+                track_numbers.1
+            };
+            let source_clip_set = self.write_track(
+                package_set,
+                2 + i as u32,
+                track_number,
+                &OpAtomBuilder::data_definition(is_picture)?,
+                duration,
+                &G_SOURCECLIP_SET_KEY,
+            )?;
+            OpAtomBuilder::set_source_clip(source_clip_set, uid, 1)?;
+        }
+        if is_complete && !self.markers.is_empty() {
+            self.write_markers(package_set, 2 + self.material_tracks.len() as u32)?;
+        }
+        if let Some(app_code) = self.app_code {
+            package_set.set_int32(&G_GENERICPACKAGE_APPCODE_ITEM_KEY, app_code)?;
+        }
+        for &(ref name, ref value) in &self.mob_attributes {
+            package_set.attach_mob_attribute(name, value)?;
+        }
+        for &(ref name, ref value) in &self.user_comments {
+            package_set.attach_user_comment(name, value)?;
+        }
+
+        /* File source package */
+        let package_set = self.write_package(
+            writer,
+            &G_SOURCEPACKAGE_SET_KEY,
+            &self.file_package_uid,
+            None,
+        )?;
+        content_storage_set.add_strongref(&G_CONTENTSTORAGE_PACKAGES_ITEM_KEY, package_set)?;
+        let descriptor_set = self.write_descriptor(writer, duration)?;
+        package_set.set_strongref(&G_SOURCEPACKAGE_DESCRIPTOR_ITEM_KEY, descriptor_set)?;
+        let track_number = if self.is_picture {
+            0x1501_0201
+        } else {
+            0x1601_0201
+        };
+        let source_clip_set = self.write_track(
+            package_set,
+            1,
+            track_number,
+            &data_definition,
+            duration,
+            &G_SOURCECLIP_SET_KEY,
+        )?;
+        match self.physical_package {
+            Some((_, _, ref uid)) => OpAtomBuilder::set_source_clip(source_clip_set, uid, 1)?,
+            None => OpAtomBuilder::set_source_clip(source_clip_set, &MXFUmid::default(), 0)?,
+        }
+        if let Some(ref archive) = self.archive {
+            if is_complete {
+                let sequence_set = self.write_event_track(package_set, 2)?;
+                OpAtomBuilder::write_archive_metadata(archive, preface_set, sequence_set)?;
+            }
+        }
+
+        self.write_physical_package(writer, content_storage_set)
+    }
+
+    pub fn write(&self, filename: &Path) -> Result<(), String> {
+        let model = Arc::new(DataModel::new()?);
+        self.write_with_model(filename, &model)
+    }
+
+    pub fn write_with_model(&self, filename: &Path, model: &Arc<DataModel>) -> Result<(), String> {
+        if self.timecode.is_some() && self.physical_package.is_none() {
+            return Err("Timecode requires a physical package.".to_string());
+        }
+        if self.duration < 0 {
+            return Err("Duration is negative.".to_string());
+        }
+
+        let operational_pattern = label("OP-Atom 1 Track 1 SourceClip")?;
+        let essence_containers = [self.essence_container_label()?];
+        let edit_unit_byte_count = self.edit_unit_byte_count();
+
+        let mut writer = MXFWriter::create(filename, model)?;
+        self.write_header_metadata(&writer, !self.open_header)?;
+
+        writer.write_partition(
+            PartitionKind::Header,
+            0,
+            0,
+            &operational_pattern,
+            &essence_containers,
+        )?;
+        if self.open_header {
+            writer.set_partition_status(false, false)?;
+        }
+        writer.write_header_metadata(self.header_fill)?;
+
+        writer.write_partition(
+            PartitionKind::Body,
+            BODY_SID,
+            0,
+            &operational_pattern,
+            &essence_containers,
+        )?;
+        let essence = vec![0; edit_unit_byte_count as usize * self.duration as usize];
+        writer.write_essence_element(&essence_element_key(self.is_picture), &essence)?;
+
+        writer.write_partition(
+            PartitionKind::Footer,
+            0,
+            INDEX_SID,
+            &operational_pattern,
+            &essence_containers,
+        )?;
+        if self.open_header {
+            writer.reset_header_metadata()?;
+            self.write_header_metadata(&writer, true)?;
+            writer.write_header_metadata(0)?;
+        }
+        writer.write_index_table_segment(&IndexTableSegment {
+            index_edit_rate: Some(self.edit_rate),
+            index_start_position: 0,
+            index_duration: self.duration,
+            edit_unit_byte_count: edit_unit_byte_count,
+            index_sid: INDEX_SID,
+            body_sid: BODY_SID,
+            slice_count: 0,
+            pos_table_count: 0,
+            delta_entries: Vec::new(),
+            index_entries: Vec::new(),
+        })?;
+
+        writer.finish()
+    }
+}
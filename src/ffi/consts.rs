@@ -1,5 +1,5 @@
 #![allow(unused)]
-use ffi::mxf::MXFKey;
+use crate::ffi::mxf::MXFKey;
 
 /* Extension data model */
 mxf_set_definition!(
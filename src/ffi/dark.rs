@@ -0,0 +1,426 @@
+use crate::ffi::mxf::*;
+use std::collections::HashMap;
+
+const PRIMER_PACK_KEY: [u8; 16] = [
+    0x06, 0x0e, 0x2b, 0x34, 0x02, 0x05, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x01, 0x05, 0x01, 0x00,
+];
+
+/* KLV fill key with the registry version octet (7) ignored */
+const KLV_FILL_KEY: [u8; 16] = [
+    0x06, 0x0e, 0x2b, 0x34, 0x01, 0x01, 0x01, 0x00, 0x03, 0x01, 0x02, 0x10, 0x01, 0x00, 0x00, 0x00,
+];
+
+const INSTANCE_UID_TAG: u16 = 0x3c0a;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DarkItem {
+    /* None if the local tag is missing from the primer pack */
+    pub key: Option<MXFKey>,
+    pub tag: u16,
+    pub value: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DarkSet {
+    pub key: MXFKey,
+    pub instance_uid: Option<MXFKey>,
+    pub items: Vec<DarkItem>,
+}
+
+/* Header metadata the data model does not know, as found in the file */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DarkMetadata {
+    /* Sets with an unregistered key, with all their items. KLVs which are not local sets are
+     * listed with a single item holding the whole value. */
+    pub sets: Vec<DarkSet>,
+    /* Registered sets carrying unregistered items, with only those items */
+    pub unknown_items: Vec<DarkSet>,
+}
+
+fn read_key(data: &[u8]) -> MXFKey {
+    let mut octets = [0; 16];
+    octets.copy_from_slice(&data[..16]);
+    MXFKey::from(octets)
+}
+
+fn read_u16(data: &[u8]) -> u16 {
+    u16::from(data[0]) << 8 | u16::from(data[1])
+}
+
+fn is_fill_key(key: &[u8]) -> bool {
+    key[..7] == KLV_FILL_KEY[..7] && key[8..] == KLV_FILL_KEY[8..]
+}
+
+/* Reads a KLV, returning the key, the value and the remaining data. None if truncated. */
+fn read_klv(data: &[u8]) -> Option<(&[u8], &[u8], &[u8])> {
+    if data.len() < 17 {
+        return None;
+    }
+
+    /* BER length, see SMPTE 336 */
+    let (value_start, len) = if data[16] < 0x80 {
+        (17, u64::from(data[16]))
+    } else {
+        let llen = (data[16] & 0x7f) as usize;
+        if llen == 0 || llen > 8 || data.len() < 17 + llen {
+            return None;
+        }
+        let len = data[17..17 + llen]
+            .iter()
+            .fold(0, |value, &byte| value << 8 | u64::from(byte));
+        (17 + llen, len)
+    };
+    if len > (data.len() - value_start) as u64 {
+        return None;
+    }
+    let value_end = value_start + len as usize;
+
+    Some((
+        &data[..16],
+        &data[value_start..value_end],
+        &data[value_end..],
+    ))
+}
+
+/* Splits the data into whole KLVs, each with its key and value. Stops at the first truncated
+ * KLV. */
+fn read_klvs(data: &[u8]) -> Vec<(&[u8], &[u8], &[u8])> {
+    let mut klvs = Vec::new();
+    let mut data = data;
+    while let Some((key, value, rest)) = read_klv(data) {
+        klvs.push((&data[..data.len() - rest.len()], key, value));
+        data = rest;
+    }
+
+    klvs
+}
+
+/* BER length in the four byte form Avid writes, or nine bytes if it does not fit */
+fn write_ber_length(data: &mut Vec<u8>, len: u64) {
+    if len < 1 << 24 {
+        data.push(0x83);
+        data.extend_from_slice(&(len as u32).to_be_bytes()[1..]);
+    } else {
+        data.push(0x88);
+        data.extend_from_slice(&len.to_be_bytes());
+    }
+}
+
+/* KLV fill taking exactly len bytes, None if len is too short for a fill KLV */
+fn write_fill(data: &mut Vec<u8>, key: &[u8], len: usize) -> Option<()> {
+    if len == 0 {
+        return Some(());
+    }
+    if len < 17 {
+        return None;
+    }
+    data.extend_from_slice(key);
+    /* The length is written at the width the header size was chosen for, even if a shorter
+     * form would do */
+    let value_len = if len < 17 + 0x80 {
+        data.push((len - 17) as u8);
+        len - 17
+    } else if len < 20 + (1 << 24) {
+        data.push(0x83);
+        data.extend_from_slice(&((len - 20) as u32).to_be_bytes()[1..]);
+        len - 20
+    } else {
+        data.push(0x88);
+        data.extend_from_slice(&((len - 25) as u64).to_be_bytes());
+        len - 25
+    };
+    data.resize(data.len() + value_len, 0);
+
+    Some(())
+}
+
+/* Local tag to item key mapping from the primer pack batch */
+fn read_primer(value: &[u8]) -> HashMap<u16, MXFKey> {
+    let mut primer = HashMap::new();
+    if value.len() < 8 || read_u16(&value[4..6]) != 0 || read_u16(&value[6..8]) != 18 {
+        return primer;
+    }
+    for entry in value[8..].chunks(18).filter(|entry| entry.len() == 18) {
+        primer.insert(read_u16(entry), read_key(&entry[2..]));
+    }
+
+    primer
+}
+
+/* Local set items with 2 byte tags and 2 byte lengths. Stops at a truncated item. */
+fn read_local_set(value: &[u8], primer: &HashMap<u16, MXFKey>) -> Vec<DarkItem> {
+    let mut items = Vec::new();
+    let mut data = value;
+    while data.len() >= 4 {
+        let tag = read_u16(data);
+        let len = read_u16(&data[2..]) as usize;
+        if data.len() < 4 + len {
+            break;
+        }
+        items.push(DarkItem {
+            key: primer.get(&tag).cloned(),
+            tag: tag,
+            value: data[4..4 + len].to_vec(),
+        });
+        data = &data[4 + len..];
+    }
+
+    items
+}
+
+fn read_instance_uid(items: &[DarkItem]) -> Option<MXFKey> {
+    items
+        .iter()
+        .find(|item| item.tag == INSTANCE_UID_TAG && item.value.len() == 16)
+        .map(|item| read_key(&item.value))
+}
+
+/* Every local set of the header metadata bytes with its items, whether the data model knows it
+ * or not, for sets libMXF does not read. Other KLVs are skipped and parsing stops at the first
+ * truncated KLV. */
+pub fn read_local_sets(data: &[u8]) -> Vec<DarkSet> {
+    let mut sets = Vec::new();
+    let mut primer = HashMap::new();
+    for (_, key, value) in read_klvs(data) {
+        if key[..] == PRIMER_PACK_KEY[..] {
+            primer = read_primer(value);
+        } else if !is_fill_key(key) && key[5] == 0x53 {
+            let items = read_local_set(value, &primer);
+            sets.push(DarkSet {
+                key: read_key(key),
+                instance_uid: read_instance_uid(&items),
+                items: items,
+            });
+        }
+    }
+
+    sets
+}
+
+impl DarkMetadata {
+    /* Collects the dark sets and items from the header metadata bytes, starting at the primer
+     * pack. The predicate tells whether a set or item key is registered. Parsing stops at the
+     * first truncated KLV. */
+    pub fn from_header_metadata<F>(data: &[u8], is_registered: F) -> DarkMetadata
+    where
+        F: Fn(&MXFKey) -> bool,
+    {
+        let mut dark = DarkMetadata::default();
+        let mut primer = HashMap::new();
+        let mut data = data;
+
+        while let Some((key, value, rest)) = read_klv(data) {
+            data = rest;
+            if key[..] == PRIMER_PACK_KEY[..] {
+                primer = read_primer(value);
+                continue;
+            }
+            if is_fill_key(key) {
+                continue;
+            }
+
+            let set_key = read_key(key);
+            /* Local sets use 2 byte tags and 2 byte lengths, see SMPTE 336 */
+            if key[5] != 0x53 {
+                if !is_registered(&set_key) {
+                    dark.sets.push(DarkSet {
+                        key: set_key,
+                        instance_uid: None,
+                        items: vec![DarkItem {
+                            key: None,
+                            tag: 0,
+                            value: value.to_vec(),
+                        }],
+                    });
+                }
+                continue;
+            }
+
+            let items = read_local_set(value, &primer);
+            let instance_uid = read_instance_uid(&items);
+            if !is_registered(&set_key) {
+                dark.sets.push(DarkSet {
+                    key: set_key,
+                    instance_uid: instance_uid,
+                    items: items,
+                });
+                continue;
+            }
+
+            let unknown: Vec<DarkItem> = items
+                .into_iter()
+                .filter(|item| match item.key {
+                    Some(ref key) => !is_registered(key),
+                    None => true,
+                })
+                .collect();
+            if !unknown.is_empty() {
+                dark.unknown_items.push(DarkSet {
+                    key: set_key,
+                    instance_uid: instance_uid,
+                    items: unknown,
+                });
+            }
+        }
+
+        dark
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty() && self.unknown_items.is_empty()
+    }
+
+    /* Copies the dark sets of the original header metadata into header metadata re-encoded by
+     * libMXF, which drops them when reading. They go after the sets in the space of the trailing
+     * fill and their local tags are added to the primer pack. Fails if they do not fit, if a tag
+     * is taken by another item, or if unregistered items of registered sets were dropped. */
+    pub fn splice_dark_sets<F>(
+        original: &[u8],
+        encoded: &[u8],
+        is_registered: F,
+    ) -> Result<Vec<u8>, String>
+    where
+        F: Fn(&MXFKey) -> bool,
+    {
+        let unknown_item_count = |dark: &DarkMetadata| -> usize {
+            dark.unknown_items.iter().map(|set| set.items.len()).sum()
+        };
+        let dark = DarkMetadata::from_header_metadata(original, &is_registered);
+        if unknown_item_count(&DarkMetadata::from_header_metadata(encoded, &is_registered))
+            < unknown_item_count(&dark)
+        {
+            return Err("Items unknown to the data model would be lost.".to_string());
+        }
+        if dark.sets.is_empty() {
+            return Ok(encoded.to_vec());
+        }
+
+        let mut original_primer = HashMap::new();
+        let mut dark_klvs = Vec::new();
+        for (klv, key, value) in read_klvs(original) {
+            if key[..] == PRIMER_PACK_KEY[..] {
+                original_primer = read_primer(value);
+            } else if !is_fill_key(key) && !is_registered(&read_key(key)) {
+                dark_klvs.push(klv);
+            }
+        }
+
+        /* The encoded metadata is the primer pack, the sets and the trailing fill */
+        let klvs = read_klvs(encoded);
+        if klvs.iter().map(|&(klv, _, _)| klv.len()).sum::<usize>() != encoded.len() {
+            return Err("Could not parse the encoded header metadata.".to_string());
+        }
+        let (primer_klv, primer_value) = match klvs.first() {
+            Some(&(klv, key, value)) if key[..] == PRIMER_PACK_KEY[..] && value.len() >= 8 => {
+                (klv, value)
+            }
+            _ => return Err("Encoded header metadata has no primer pack.".to_string()),
+        };
+        let sets_end = klvs
+            .iter()
+            .rposition(|&(_, key, _)| !is_fill_key(key))
+            .map_or(0, |last| {
+                klvs[..=last].iter().map(|&(klv, _, _)| klv.len()).sum()
+            });
+        let fill_key = klvs
+            .last()
+            .filter(|&&(_, key, _)| is_fill_key(key))
+            .map(|&(_, key, _)| key);
+
+        /* Add the local tags of the dark sets to the primer pack */
+        let mut primer = read_primer(primer_value);
+        let mut new_entries = Vec::new();
+        for set in &dark.sets {
+            for item in &set.items {
+                let key = match original_primer.get(&item.tag) {
+                    Some(key) => *key,
+                    /* Already unresolvable in the original */
+                    None => continue,
+                };
+                match primer.get(&item.tag) {
+                    Some(existing) if *existing == key => continue,
+                    Some(_) => {
+                        return Err(format!(
+                            "Local tag 0x{:04x} of dark set {:?} is used by another item.",
+                            item.tag, set.key
+                        ))
+                    }
+                    None => {}
+                }
+                primer.insert(item.tag, key);
+                new_entries.push((item.tag, key));
+            }
+        }
+
+        let mut data = Vec::with_capacity(encoded.len());
+        if new_entries.is_empty() {
+            data.extend_from_slice(primer_klv);
+        } else {
+            let mut batch = Vec::with_capacity(primer_value.len() + new_entries.len() * 18);
+            let count = (primer_value.len() - 8) / 18 + new_entries.len();
+            batch.extend_from_slice(&(count as u32).to_be_bytes());
+            batch.extend_from_slice(&18u32.to_be_bytes());
+            batch.extend_from_slice(&primer_value[8..]);
+            for (tag, key) in new_entries {
+                batch.extend_from_slice(&tag.to_be_bytes());
+                batch.extend_from_slice(&key.as_bytes());
+            }
+            data.extend_from_slice(&PRIMER_PACK_KEY);
+            write_ber_length(&mut data, batch.len() as u64);
+            data.extend_from_slice(&batch);
+        }
+        data.extend_from_slice(&encoded[primer_klv.len()..sets_end]);
+        for klv in dark_klvs {
+            data.extend_from_slice(klv);
+        }
+
+        let remaining = encoded.len().checked_sub(data.len());
+        let fits = match (remaining, fill_key) {
+            (Some(remaining), Some(key)) => write_fill(&mut data, key, remaining).is_some(),
+            (Some(remaining), None) => remaining == 0,
+            (None, _) => false,
+        };
+        if !fits {
+            return Err(format!(
+                "Dark sets do not fit in the {} bytes of header metadata space.",
+                encoded.len()
+            ));
+        }
+
+        if data.len() != encoded.len() {
+            return Err("Could not fill the header metadata space.".to_string());
+        }
+
+        Ok(data)
+    }
+}
+
+impl MXFFile {
+    fn is_registered(&self, key: &MXFKey) -> bool {
+        let datamodel = self.datamodel();
+        datamodel.has_set_def(key) || datamodel.item_def(key).is_some()
+    }
+
+    /* Dark sets and unknown items in the header metadata, checked against the file's data
+     * model */
+    pub fn dark_metadata(&self) -> Result<DarkMetadata, String> {
+        let data = self.header_metadata_bytes()?;
+
+        Ok(DarkMetadata::from_header_metadata(&data, |key| {
+            self.is_registered(key)
+        }))
+    }
+
+    /* Like encode_header_metadata, keeping the dark sets of the original header metadata bytes,
+     * see DarkMetadata::splice_dark_sets */
+    pub fn encode_header_metadata_with_dark_sets(
+        &self,
+        original: &[u8],
+    ) -> Result<(u64, Vec<u8>), String> {
+        let (offset, encoded) = self.encode_header_metadata()?;
+        let data =
+            DarkMetadata::splice_dark_sets(original, &encoded, |key| self.is_registered(key))?;
+
+        Ok((offset, data))
+    }
+}
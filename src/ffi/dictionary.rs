@@ -0,0 +1,403 @@
+use crate::ffi::consts::*;
+use crate::ffi::dark::{read_local_sets, DarkSet};
+use crate::ffi::mxf::*;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/* Dynamic local tags are allocated by the primer pack of each file */
+const DYNAMIC_TAG_START: u16 = 0x8000;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Definition {
+    pub identification: Option<MXFKey>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+/* Definitions of the dictionary referenced by the preface */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dictionary {
+    pub operation_definitions: Vec<Definition>,
+    pub parameter_definitions: Vec<Definition>,
+    pub data_definitions: Vec<Definition>,
+    pub plugin_definitions: Vec<Definition>,
+    pub codec_definitions: Vec<Definition>,
+    pub container_definitions: Vec<Definition>,
+    pub interpolation_definitions: Vec<Definition>,
+    pub klv_data_definitions: Vec<Definition>,
+    pub tagged_value_definitions: Vec<Definition>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PropertyDefinition {
+    pub identification: Option<MXFKey>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /* Identification of the type definition */
+    pub property_type: Option<MXFKey>,
+    pub is_optional: Option<bool>,
+    pub local_identification: Option<u16>,
+    pub is_unique_identifier: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClassDefinition {
+    pub identification: Option<MXFKey>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /* Identification of the parent class definition */
+    pub parent_class: Option<MXFKey>,
+    pub is_concrete: Option<bool>,
+    pub properties: Vec<PropertyDefinition>,
+}
+
+/* Class and type definitions of the Avid meta-dictionary, which describe the sets and items
+ * written by the application including its extensions */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetaDictionary {
+    pub class_definitions: Vec<ClassDefinition>,
+    pub type_definitions: Vec<Definition>,
+}
+
+fn read_definition(set: &mut MXFMetadataSet) -> Definition {
+    Definition {
+        identification: set.get_ul(&G_DEFINITIONOBJECT_IDENTIFICATION_ITEM_KEY),
+        name: set.get_string(&G_DEFINITIONOBJECT_NAME_ITEM_KEY),
+        description: set.get_string(&G_DEFINITIONOBJECT_DESCRIPTION_ITEM_KEY),
+    }
+}
+
+/* Raw item values of the meta-dictionary sets, which libMXF skips when reading Avid header
+ * metadata */
+fn item_value<'a>(set: &'a DarkSet, mxfkey: &MXFKey) -> Option<&'a [u8]> {
+    set.items
+        .iter()
+        .find(|item| item.key.as_ref() == Some(mxfkey))
+        .map(|item| item.value.as_slice())
+}
+
+fn read_raw_key(set: &DarkSet, mxfkey: &MXFKey) -> Option<MXFKey> {
+    match item_value(set, mxfkey) {
+        Some(value) if value.len() == 16 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(value);
+            Some(MXFKey::from(octets))
+        }
+        _ => None,
+    }
+}
+
+/* UTF-16 big endian string, up to the first null */
+fn read_raw_string(set: &DarkSet, mxfkey: &MXFKey) -> Option<String> {
+    let value = item_value(set, mxfkey)?;
+    let utf16: Vec<u16> = value
+        .chunks(2)
+        .filter(|chunk| chunk.len() == 2)
+        .map(|chunk| u16::from(chunk[0]) << 8 | u16::from(chunk[1]))
+        .take_while(|&c| c != 0)
+        .collect();
+
+    Some(String::from_utf16_lossy(&utf16))
+}
+
+fn read_raw_boolean(set: &DarkSet, mxfkey: &MXFKey) -> Option<bool> {
+    match item_value(set, mxfkey) {
+        Some(value) if value.len() == 1 => Some(value[0] != 0),
+        _ => None,
+    }
+}
+
+fn read_raw_uint16(set: &DarkSet, mxfkey: &MXFKey) -> Option<u16> {
+    match item_value(set, mxfkey) {
+        Some(value) if value.len() == 2 => Some(u16::from(value[0]) << 8 | u16::from(value[1])),
+        _ => None,
+    }
+}
+
+/* Sets strong referenced by an array or batch of instance UIDs */
+fn read_raw_strongrefs<'a>(
+    sets: &HashMap<MXFKey, &'a DarkSet>,
+    set: &DarkSet,
+    mxfkey: &MXFKey,
+) -> Vec<&'a DarkSet> {
+    let value = match item_value(set, mxfkey) {
+        Some(value) if value.len() >= 8 => value,
+        _ => return Vec::new(),
+    };
+
+    value[8..]
+        .chunks(16)
+        .filter(|chunk| chunk.len() == 16)
+        .filter_map(|chunk| {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(chunk);
+            sets.get(&MXFKey::from(octets)).cloned()
+        })
+        .collect()
+}
+
+fn read_meta_definition(set: &DarkSet) -> Definition {
+    Definition {
+        identification: read_raw_key(set, &G_METADEFINITION_IDENTIFICATION_ITEM_KEY),
+        name: read_raw_string(set, &G_METADEFINITION_NAME_ITEM_KEY),
+        description: read_raw_string(set, &G_METADEFINITION_DESCRIPTION_ITEM_KEY),
+    }
+}
+
+/* Sets strong referenced by an array or batch item */
+fn read_strongrefs(
+    file: &MXFFile,
+    set: &mut MXFMetadataSet,
+    mxfkey: &MXFKey,
+) -> Vec<&'static mut MXFMetadataSet> {
+    let array_iter = match set.initialize_array_iterator(mxfkey) {
+        Some(x) => x,
+        None => return Vec::new(),
+    };
+
+    /* Dark sets not registered in dictionary are left out */
+    array_iter
+        .filter_map(|item| file.headerdata().get_strongref(item))
+        .collect()
+}
+
+fn read_definitions(
+    file: &MXFFile,
+    dictionary_set: &mut MXFMetadataSet,
+    mxfkey: &MXFKey,
+) -> Vec<Definition> {
+    read_strongrefs(file, dictionary_set, mxfkey)
+        .into_iter()
+        .map(read_definition)
+        .collect()
+}
+
+fn read_property_definition(set: &DarkSet) -> PropertyDefinition {
+    let definition = read_meta_definition(set);
+    PropertyDefinition {
+        identification: definition.identification,
+        name: definition.name,
+        description: definition.description,
+        property_type: read_raw_key(set, &G_PROPERTYDEFINITION_TYPE_ITEM_KEY),
+        is_optional: read_raw_boolean(set, &G_PROPERTYDEFINITION_ISOPTIONAL_ITEM_KEY),
+        local_identification: read_raw_uint16(
+            set,
+            &G_PROPERTYDEFINITION_LOCALIDENTIFICATION_ITEM_KEY,
+        ),
+        is_unique_identifier: read_raw_boolean(
+            set,
+            &G_PROPERTYDEFINITION_ISUNIQUEIDENTIFIER_ITEM_KEY,
+        ),
+    }
+}
+
+impl Dictionary {
+    pub fn from_file(filename: &Path) -> Result<Dictionary, String> {
+        let file = MXFFile::open(filename, false)?;
+        Dictionary::from_mxf_file(&file)
+    }
+
+    /* Empty if the preface has no dictionary */
+    pub fn from_mxf_file(file: &MXFFile) -> Result<Dictionary, String> {
+        let mut dictionary = Dictionary::default();
+
+        let preface_set = file
+            .headerdata()
+            .find_singular_set_by_key(&G_PREFACE_SET_KEY)?;
+        let dictionary_set = match preface_set.get_strongref(&G_PREFACE_DICTIONARY_ITEM_KEY) {
+            Some(x) => x,
+            None => return Ok(dictionary),
+        };
+
+        dictionary.operation_definitions = read_definitions(
+            file,
+            dictionary_set,
+            &G_DICTIONARY_OPERATIONDEFINITIONS_ITEM_KEY,
+        );
+        dictionary.parameter_definitions = read_definitions(
+            file,
+            dictionary_set,
+            &G_DICTIONARY_PARAMETERDEFINITIONS_ITEM_KEY,
+        );
+        dictionary.data_definitions =
+            read_definitions(file, dictionary_set, &G_DICTIONARY_DATADEFINITIONS_ITEM_KEY);
+        dictionary.plugin_definitions = read_definitions(
+            file,
+            dictionary_set,
+            &G_DICTIONARY_PLUGINDEFINITIONS_ITEM_KEY,
+        );
+        dictionary.codec_definitions = read_definitions(
+            file,
+            dictionary_set,
+            &G_DICTIONARY_CODECDEFINITIONS_ITEM_KEY,
+        );
+        dictionary.container_definitions = read_definitions(
+            file,
+            dictionary_set,
+            &G_DICTIONARY_CONTAINERDEFINITIONS_ITEM_KEY,
+        );
+        dictionary.interpolation_definitions = read_definitions(
+            file,
+            dictionary_set,
+            &G_DICTIONARY_INTERPOLATIONDEFINITIONS_ITEM_KEY,
+        );
+        dictionary.klv_data_definitions = read_definitions(
+            file,
+            dictionary_set,
+            &G_DICTIONARY_KLVDATADEFINITIONS_ITEM_KEY,
+        );
+        dictionary.tagged_value_definitions = read_definitions(
+            file,
+            dictionary_set,
+            &G_DICTIONARY_TAGGEDVALUEDEFINITIONS_ITEM_KEY,
+        );
+
+        Ok(dictionary)
+    }
+}
+
+impl MetaDictionary {
+    pub fn from_file(filename: &Path) -> Result<MetaDictionary, String> {
+        let file = MXFFile::open(filename, false)?;
+        MetaDictionary::from_mxf_file(&file)
+    }
+
+    /* Empty if the file has no meta-dictionary */
+    pub fn from_mxf_file(file: &MXFFile) -> Result<MetaDictionary, String> {
+        Ok(MetaDictionary::from_header_metadata(
+            &file.header_metadata_bytes()?,
+        ))
+    }
+
+    /* Parses the meta-dictionary from the header metadata bytes, starting at the primer pack.
+     * libMXF skips the meta-dictionary sets when reading Avid header metadata. */
+    pub fn from_header_metadata(data: &[u8]) -> MetaDictionary {
+        let mut metadictionary = MetaDictionary::default();
+
+        let local_sets = read_local_sets(data);
+        let sets: HashMap<MXFKey, &DarkSet> = local_sets
+            .iter()
+            .filter_map(|set| set.instance_uid.map(|instance_uid| (instance_uid, set)))
+            .collect();
+        let metadictionary_set = match local_sets
+            .iter()
+            .find(|set| set.key == G_METADICTIONARY_SET_KEY)
+        {
+            Some(x) => x,
+            None => return metadictionary,
+        };
+
+        for class_set in read_raw_strongrefs(
+            &sets,
+            metadictionary_set,
+            &G_METADICTIONARY_CLASSDEFINITIONS_ITEM_KEY,
+        ) {
+            let definition = read_meta_definition(class_set);
+            metadictionary.class_definitions.push(ClassDefinition {
+                identification: definition.identification,
+                name: definition.name,
+                description: definition.description,
+                parent_class: read_raw_key(class_set, &G_CLASSDEFINITION_PARENTCLASS_ITEM_KEY),
+                is_concrete: read_raw_boolean(class_set, &G_CLASSDEFINITION_ISCONCRETE_ITEM_KEY),
+                properties: read_raw_strongrefs(
+                    &sets,
+                    class_set,
+                    &G_CLASSDEFINITION_PROPERTIES_ITEM_KEY,
+                )
+                .into_iter()
+                .map(read_property_definition)
+                .collect(),
+            });
+        }
+        metadictionary.type_definitions = read_raw_strongrefs(
+            &sets,
+            metadictionary_set,
+            &G_METADICTIONARY_TYPEDEFINITIONS_ITEM_KEY,
+        )
+        .into_iter()
+        .map(read_meta_definition)
+        .collect();
+
+        metadictionary
+    }
+
+    /* Data model with the classes and properties missing from the built-in data model
+     * registered. Classes are only registered once their parent is known, and the properties
+     * have an unknown type so their values are read as bytes. */
+    pub fn datamodel(&self) -> Result<DataModel, String> {
+        DataModel::with_registered(|datamodel| {
+            let mut pending: Vec<&ClassDefinition> = self
+                .class_definitions
+                .iter()
+                .filter(|class| match class.identification {
+                    Some(ref key) => !datamodel.has_set_def(key),
+                    None => false,
+                })
+                .collect();
+            let mut registered = HashSet::new();
+
+            /* Register parents before their subclasses */
+            loop {
+                let count = pending.len();
+                let mut remaining = Vec::new();
+                for class in pending {
+                    let key = class.identification.unwrap();
+                    match class.parent_class {
+                        Some(ref parent)
+                            if datamodel.has_set_def(parent) || registered.contains(parent) =>
+                        {
+                            datamodel.register_set_def(
+                                class.name.as_ref().map_or("", |name| name.as_str()),
+                                parent,
+                                &key,
+                            )?;
+                            registered.insert(key);
+                        }
+                        _ => remaining.push(class),
+                    }
+                }
+                pending = remaining;
+                if pending.is_empty() || pending.len() == count {
+                    break;
+                }
+            }
+
+            for class in &self.class_definitions {
+                let set_key = match class.identification {
+                    Some(ref key) if datamodel.has_set_def(key) || registered.contains(key) => key,
+                    _ => continue,
+                };
+                for property in &class.properties {
+                    let key = match property.identification {
+                        Some(ref key) if datamodel.item_def(key).is_none() => key,
+                        _ => continue,
+                    };
+                    let tag = match property.local_identification {
+                        Some(tag) if tag < DYNAMIC_TAG_START => tag,
+                        _ => 0,
+                    };
+                    datamodel.register_item_def(
+                        property.name.as_ref().map_or("", |name| name.as_str()),
+                        set_key,
+                        key,
+                        tag,
+                        !property.is_optional.unwrap_or(true),
+                    )?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl MXFFile {
+    /* Opens the file with its meta-dictionary extensions registered, so sets and items the
+     * built-in data model does not know are read instead of left dark */
+    pub fn open_with_extensions(filename: &Path, op_atom_only: bool) -> Result<MXFFile, String> {
+        let metadictionary = MetaDictionary::from_file(filename)?;
+        let model = Arc::new(metadictionary.datamodel()?);
+        MXFFile::open_with_model(filename, op_atom_only, MetadataSource::Header, &model)
+    }
+}
@@ -0,0 +1,530 @@
+use crate::ffi::mxf::*;
+use num_rational::Rational32;
+use std::collections::HashSet;
+use std::fmt;
+
+/* Byte values longer than this are shortened in the text rendering */
+const TEXT_BYTES_LIMIT: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DumpValue {
+    Int(i64),
+    UInt(u64),
+    Boolean(bool),
+    String(String),
+    Key(MXFKey),
+    Umid(MXFUmid),
+    Rational(Rational32),
+    Timestamp(String),
+    Compound(Vec<(String, DumpValue)>),
+    Array(Vec<DumpValue>),
+    Set(Box<DumpSet>),
+    /* Strong reference to a set that is missing, dark or already expanded above */
+    UnresolvedRef(MXFKey),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpItem {
+    pub key: MXFKey,
+    pub tag: u16,
+    pub name: Option<String>,
+    pub type_name: Option<String>,
+    pub value: DumpValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpSet {
+    pub key: MXFKey,
+    pub class_name: Option<String>,
+    pub instance_uid: MXFKey,
+    pub items: Vec<DumpItem>,
+}
+
+/* Header metadata as a tree. The roots are the sets not strong referenced by another set, which
+ * is the preface in a valid file. */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetadataDump {
+    pub sets: Vec<DumpSet>,
+}
+
+fn read_uint(value: &[u8]) -> u64 {
+    value
+        .iter()
+        .fold(0, |acc, &octet| acc << 8 | u64::from(octet))
+}
+
+fn read_key(value: &[u8]) -> MXFKey {
+    let mut octets = [0; 16];
+    octets.copy_from_slice(&value[..16]);
+    MXFKey::from(octets)
+}
+
+fn read_umid(value: &[u8]) -> MXFUmid {
+    let mut octets = [0; 32];
+    octets.copy_from_slice(&value[..32]);
+    MXFUmid::from(octets)
+}
+
+/* Elements of an array or batch value with its element count and length header */
+fn array_elements(value: &[u8]) -> Option<Vec<&[u8]>> {
+    if value.len() < 8 {
+        return None;
+    }
+    let count = read_uint(&value[0..4]) as usize;
+    let len = read_uint(&value[4..8]) as usize;
+    if len == 0 || value.len() != 8 + count * len {
+        return None;
+    }
+
+    Some(value[8..].chunks(len).collect())
+}
+
+fn is_strong_ref(item_type: &ItemType) -> bool {
+    match *item_type {
+        ItemType::Interpret { ref name, .. } => name.starts_with("StrongRef"),
+        _ => false,
+    }
+}
+
+/* Instance UIDs referenced by a strong reference or an array of them */
+fn strong_refs(item_type: &ItemType, value: &[u8], uids: &mut HashSet<MXFKey>) {
+    match *item_type {
+        ItemType::Interpret { .. } if is_strong_ref(item_type) && value.len() == 16 => {
+            uids.insert(read_key(value));
+        }
+        ItemType::Array { ref element, .. } if is_strong_ref(element) => {
+            for element_value in array_elements(value).unwrap_or_default() {
+                strong_refs(element, element_value, uids);
+            }
+        }
+        _ => {}
+    }
+}
+
+struct Decoder<'a> {
+    file: &'a MXFFile,
+    /* Instance UIDs of the sets being expanded, to stop at reference cycles */
+    path: Vec<MXFKey>,
+}
+
+impl<'a> Decoder<'a> {
+    fn dump_set(&mut self, set: &MXFMetadataSet) -> DumpSet {
+        self.path.push(set.instance_uid);
+        let datamodel = self.file.headerdata().datamodel();
+        let items = set
+            .get_items()
+            .into_iter()
+            .map(|item| {
+                let item_def = datamodel.item_def(&item.key);
+                let value = match item_def {
+                    Some(ref item_def) => self.decode(&item_def.item_type, item.get_value()),
+                    None => DumpValue::Bytes(item.get_value().to_vec()),
+                };
+                DumpItem {
+                    key: item.key,
+                    tag: item.tag,
+                    name: item_def.as_ref().and_then(|item_def| item_def.name.clone()),
+                    type_name: item_def.map(|item_def| item_def.item_type.name().to_string()),
+                    value: value,
+                }
+            })
+            .collect();
+        self.path.pop();
+
+        DumpSet {
+            key: set.key,
+            class_name: datamodel.set_def_name(&set.key),
+            instance_uid: set.instance_uid,
+            items: items,
+        }
+    }
+
+    fn strong_ref(&mut self, value: &[u8]) -> DumpValue {
+        let uid = read_key(value);
+        if self.path.contains(&uid) {
+            return DumpValue::UnresolvedRef(uid);
+        }
+        match self.file.headerdata().get_strongref(&value[0]) {
+            Some(set) => DumpValue::Set(Box::new(self.dump_set(set))),
+            None => DumpValue::UnresolvedRef(uid),
+        }
+    }
+
+    fn decode(&mut self, item_type: &ItemType, value: &[u8]) -> DumpValue {
+        match *item_type {
+            ItemType::Interpret {
+                ref name,
+                ref base,
+                fixed_size,
+            } => match (name.as_str(), &**base) {
+                ("Boolean", _) if value.len() == 1 => DumpValue::Boolean(value[0] != 0),
+                _ if is_strong_ref(item_type) && value.len() == 16 => self.strong_ref(value),
+                /* Fixed size interpretations of an array such as universal labels */
+                (_, &ItemType::Array { ref element, .. }) if fixed_size > 0 => {
+                    let fixed_type = ItemType::Array {
+                        name: name.clone(),
+                        element: element.clone(),
+                        fixed_size: fixed_size,
+                    };
+                    self.decode(&fixed_type, value)
+                }
+                _ => self.decode(base, value),
+            },
+            ItemType::Basic { ref name, size } => {
+                if value.len() != size as usize || size == 0 || size > 8 {
+                    return DumpValue::Bytes(value.to_vec());
+                }
+                let unsigned = read_uint(value);
+                if name.starts_with("Int") {
+                    let shift = 64 - 8 * size;
+                    DumpValue::Int(((unsigned << shift) as i64) >> shift)
+                } else {
+                    DumpValue::UInt(unsigned)
+                }
+            }
+            ItemType::Array {
+                ref name,
+                ref element,
+                fixed_size,
+            } => {
+                if name.starts_with("UTF16String") {
+                    let utf16: Vec<u16> = value
+                        .chunks(2)
+                        .filter(|pair| pair.len() == 2)
+                        .map(|pair| u16::from(pair[0]) << 8 | u16::from(pair[1]))
+                        .take_while(|&c| c != 0)
+                        .collect();
+                    return DumpValue::String(String::from_utf16_lossy(&utf16));
+                }
+                if name.starts_with("ISO7String") || name.starts_with("UTF8String") {
+                    let end = value.iter().position(|&c| c == 0).unwrap_or(value.len());
+                    return DumpValue::String(String::from_utf8_lossy(&value[..end]).into_owned());
+                }
+                match (element.size(), fixed_size) {
+                    /* Universal labels, UUIDs and UMIDs */
+                    (Some(1), 16) if value.len() == 16 => DumpValue::Key(read_key(value)),
+                    (Some(1), 32) if value.len() == 32 => DumpValue::Umid(read_umid(value)),
+                    (Some(1), _) => DumpValue::Bytes(value.to_vec()),
+                    (Some(size), fixed_size)
+                        if fixed_size > 0 && value.len() == size * fixed_size as usize =>
+                    {
+                        DumpValue::Array(
+                            value
+                                .chunks(size)
+                                .map(|element_value| self.decode(element, element_value))
+                                .collect(),
+                        )
+                    }
+                    _ => match array_elements(value) {
+                        Some(elements) => DumpValue::Array(
+                            elements
+                                .into_iter()
+                                .map(|element_value| self.decode(element, element_value))
+                                .collect(),
+                        ),
+                        None => DumpValue::Bytes(value.to_vec()),
+                    },
+                }
+            }
+            ItemType::Compound {
+                ref name,
+                ref members,
+            } => match name.as_str() {
+                "Rational" if value.len() == 8 => DumpValue::Rational(Rational32::new_raw(
+                    read_uint(&value[0..4]) as i32,
+                    read_uint(&value[4..8]) as i32,
+                )),
+                "Timestamp" if value.len() == 8 => DumpValue::Timestamp(format!(
+                    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+                    read_uint(&value[0..2]) as i16,
+                    value[2],
+                    value[3],
+                    value[4],
+                    value[5],
+                    value[6],
+                    u32::from(value[7]) * 4
+                )),
+                _ => {
+                    let sizes: Option<Vec<usize>> = members
+                        .iter()
+                        .map(|&(_, ref member)| member.size())
+                        .collect();
+                    match sizes {
+                        Some(ref sizes) if sizes.iter().sum::<usize>() == value.len() => {
+                            let mut offset = 0;
+                            let mut values = Vec::with_capacity(members.len());
+                            for (&(ref name, ref member), &size) in members.iter().zip(sizes) {
+                                values.push((
+                                    name.clone(),
+                                    self.decode(member, &value[offset..offset + size]),
+                                ));
+                                offset += size;
+                            }
+                            DumpValue::Compound(values)
+                        }
+                        _ => DumpValue::Bytes(value.to_vec()),
+                    }
+                }
+            },
+            ItemType::Unknown => DumpValue::Bytes(value.to_vec()),
+        }
+    }
+}
+
+impl MXFFile {
+    /* Every set of the header metadata with strong references expanded. Items are decoded with
+     * their data model definition; items and sets the model does not know are kept as bytes and
+     * without a name. */
+    pub fn dump(&self) -> MetadataDump {
+        let datamodel = self.headerdata().datamodel();
+        let sets: Vec<&mut MXFMetadataSet> = self
+            .headerdata()
+            .sets
+            .get_iter()
+            .map(<&mut MXFMetadataSet>::from)
+            .collect();
+
+        let mut referenced = HashSet::new();
+        for set in &sets {
+            for item in set.get_items() {
+                if let Some(item_def) = datamodel.item_def(&item.key) {
+                    strong_refs(&item_def.item_type, item.get_value(), &mut referenced);
+                }
+            }
+        }
+
+        let mut decoder = Decoder {
+            file: self,
+            path: Vec::new(),
+        };
+        MetadataDump {
+            sets: sets
+                .into_iter()
+                .filter(|set| !referenced.contains(&set.instance_uid))
+                .map(|set| decoder.dump_set(set))
+                .collect(),
+        }
+    }
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn to_hex(value: &[u8]) -> String {
+    value.iter().map(|octet| format!("{:02x}", octet)).collect()
+}
+
+fn write_json_value(out: &mut String, value: &DumpValue) {
+    match *value {
+        DumpValue::Int(value) => out.push_str(&value.to_string()),
+        DumpValue::UInt(value) => out.push_str(&value.to_string()),
+        DumpValue::Boolean(value) => out.push_str(if value { "true" } else { "false" }),
+        DumpValue::String(ref value) | DumpValue::Timestamp(ref value) => {
+            write_json_string(out, value)
+        }
+        DumpValue::Key(ref key) => write_json_string(out, &key.to_string()),
+        DumpValue::Umid(ref umid) => write_json_string(out, &umid.to_string()),
+        DumpValue::Rational(ref rational) => write_json_string(out, &rational.to_string()),
+        DumpValue::Compound(ref members) => {
+            out.push('{');
+            for (i, &(ref name, ref value)) in members.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_json_string(out, name);
+                out.push(':');
+                write_json_value(out, value);
+            }
+            out.push('}');
+        }
+        DumpValue::Array(ref values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_json_value(out, value);
+            }
+            out.push(']');
+        }
+        DumpValue::Set(ref set) => write_json_set(out, set),
+        DumpValue::UnresolvedRef(ref uid) => {
+            out.push_str("{\"unresolved\":");
+            write_json_string(out, &uid.to_string());
+            out.push('}');
+        }
+        DumpValue::Bytes(ref value) => write_json_string(out, &to_hex(value)),
+    }
+}
+
+fn write_json_set(out: &mut String, set: &DumpSet) {
+    out.push_str("{\"class\":");
+    match set.class_name {
+        Some(ref name) => write_json_string(out, name),
+        None => out.push_str("null"),
+    }
+    out.push_str(",\"key\":");
+    write_json_string(out, &set.key.to_string());
+    out.push_str(",\"instance_uid\":");
+    write_json_string(out, &set.instance_uid.to_string());
+    out.push_str(",\"items\":[");
+    for (i, item) in set.items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"name\":");
+        match item.name {
+            Some(ref name) => write_json_string(out, name),
+            None => out.push_str("null"),
+        }
+        out.push_str(",\"key\":");
+        write_json_string(out, &item.key.to_string());
+        out.push_str(&format!(",\"tag\":{},\"type\":", item.tag));
+        match item.type_name {
+            Some(ref name) => write_json_string(out, name),
+            None => out.push_str("null"),
+        }
+        out.push_str(",\"value\":");
+        write_json_value(out, &item.value);
+        out.push('}');
+    }
+    out.push_str("]}");
+}
+
+fn has_sets(value: &DumpValue) -> bool {
+    match *value {
+        DumpValue::Set(_) => true,
+        DumpValue::Array(ref values) => values.iter().any(has_sets),
+        _ => false,
+    }
+}
+
+fn write_text_set(f: &mut fmt::Formatter, set: &DumpSet, indent: usize) -> fmt::Result {
+    writeln!(
+        f,
+        "{:indent$}{} [{}]",
+        "",
+        set.class_name
+            .as_ref()
+            .map_or("Unknown set", |name| name.as_str()),
+        set.key,
+        indent = indent
+    )?;
+    for item in &set.items {
+        write!(f, "{:indent$}", "", indent = indent + 4)?;
+        match item.name {
+            Some(ref name) => write!(f, "{}", name)?,
+            None => write!(f, "Unknown item 0x{:04x} [{}]", item.tag, item.key)?,
+        }
+        if has_sets(&item.value) {
+            writeln!(f, ":")?;
+            write_text_nested(f, &item.value, indent + 8)?;
+        } else {
+            writeln!(f, ": {}", TextValue(&item.value))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_text_nested(f: &mut fmt::Formatter, value: &DumpValue, indent: usize) -> fmt::Result {
+    match *value {
+        DumpValue::Set(ref set) => write_text_set(f, set, indent),
+        DumpValue::Array(ref values) => {
+            for value in values {
+                write_text_nested(f, value, indent)?;
+            }
+            Ok(())
+        }
+        ref value => writeln!(f, "{:indent$}{}", "", TextValue(value), indent = indent),
+    }
+}
+
+struct TextValue<'a>(&'a DumpValue);
+
+impl<'a> fmt::Display for TextValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self.0 {
+            DumpValue::Int(value) => write!(f, "{}", value),
+            DumpValue::UInt(value) => write!(f, "{}", value),
+            DumpValue::Boolean(value) => write!(f, "{}", value),
+            DumpValue::String(ref value) | DumpValue::Timestamp(ref value) => {
+                write!(f, "{}", value)
+            }
+            DumpValue::Key(ref key) => match key.name() {
+                Some(name) => write!(f, "{} ({})", key, name),
+                None => write!(f, "{}", key),
+            },
+            DumpValue::Umid(ref umid) => write!(f, "{}", umid),
+            DumpValue::Rational(ref rational) => write!(f, "{}", rational),
+            DumpValue::Compound(ref members) => {
+                write!(f, "{{")?;
+                for (i, &(ref name, ref value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", name, TextValue(value))?;
+                }
+                write!(f, "}}")
+            }
+            DumpValue::Array(ref values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", TextValue(value))?;
+                }
+                write!(f, "]")
+            }
+            DumpValue::Set(ref set) => write!(f, "{}", set.instance_uid),
+            DumpValue::UnresolvedRef(ref uid) => write!(f, "{} (unresolved)", uid),
+            DumpValue::Bytes(ref value) if value.len() > TEXT_BYTES_LIMIT => write!(
+                f,
+                "{}... ({} bytes)",
+                to_hex(&value[..TEXT_BYTES_LIMIT]),
+                value.len()
+            ),
+            DumpValue::Bytes(ref value) => write!(f, "{}", to_hex(value)),
+        }
+    }
+}
+
+impl MetadataDump {
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        out.push('[');
+        for (i, set) in self.sets.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_json_set(&mut out, set);
+        }
+        out.push(']');
+        out
+    }
+}
+
+/* Indented text like mxfdump, with nested sets below the item referencing them */
+impl fmt::Display for MetadataDump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for set in &self.sets {
+            write_text_set(f, set, 0)?;
+        }
+        Ok(())
+    }
+}
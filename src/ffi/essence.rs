@@ -0,0 +1,106 @@
+use crate::ffi::index::IndexTable;
+use crate::ffi::mxf::MXFFile;
+
+/* The bytes of one or more edit units */
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EssenceFrame {
+    pub position: i64,
+    pub edit_units: u32,
+    pub offset: u64,
+    pub data: Vec<u8>,
+}
+
+pub struct EssenceReader<'a> {
+    file: &'a MXFFile,
+    index_table: IndexTable,
+    position: i64,
+    duration: i64,
+    block_size: u32,
+}
+
+impl<'a> EssenceReader<'a> {
+    /* Reads one edit unit at a time, a video frame or a single audio sample */
+    pub fn new(file: &'a MXFFile) -> Result<EssenceReader<'a>, String> {
+        EssenceReader::with_block_size(file, 1)
+    }
+
+    /* Reads blocks of edit units from CBR essence such as PCM audio. VBR essence is always read
+     * one edit unit at a time. */
+    pub fn with_block_size(
+        file: &'a MXFFile,
+        block_size: u32,
+    ) -> Result<EssenceReader<'a>, String> {
+        if block_size == 0 {
+            return Err("Block size must not be zero.".to_string());
+        }
+        let index_table = file.index_table()?;
+        /* Blocks must not span segments with different edit unit sizes */
+        let is_uniform = index_table.is_cbr()
+            && index_table.segments.iter().all(|segment| {
+                segment.edit_unit_byte_count == index_table.segments[0].edit_unit_byte_count
+            });
+        let block_size = if is_uniform { block_size } else { 1 };
+
+        Ok(EssenceReader {
+            file: file,
+            duration: index_table.duration(),
+            index_table: index_table,
+            position: 0,
+            block_size: block_size,
+        })
+    }
+
+    pub fn index_table(&self) -> &IndexTable {
+        &self.index_table
+    }
+
+    pub fn duration(&self) -> i64 {
+        self.duration
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    pub fn seek(&mut self, position: i64) {
+        self.position = position;
+    }
+
+    /* Reads the block of edit units starting at the position */
+    pub fn read_at(&self, position: i64) -> Result<EssenceFrame, String> {
+        if position < 0 || position >= self.duration {
+            return Err(format!("Position {} is beyond the essence.", position));
+        }
+        let edit_units = i64::from(self.block_size).min(self.duration - position) as u32;
+        let (offset, size) = self.index_table.frame_offset(position)?;
+        /* Blocks are only read from CBR essence, where every edit unit has the same size */
+        let size = size
+            .checked_mul(edit_units)
+            .ok_or(format!("Block at position {} is too large.", position))?;
+
+        Ok(EssenceFrame {
+            position: position,
+            edit_units: edit_units,
+            offset: offset,
+            data: self.file.read_bytes(offset, size)?,
+        })
+    }
+}
+
+impl<'a> Iterator for EssenceReader<'a> {
+    type Item = Result<EssenceFrame, String>;
+
+    fn next(&mut self) -> Option<Result<EssenceFrame, String>> {
+        if self.position >= self.duration {
+            return None;
+        }
+        let frame = self.read_at(self.position);
+        self.position = match frame {
+            Ok(ref frame) => self.position + i64::from(frame.edit_units),
+            /* Stop after an error */
+            Err(_) => self.duration,
+        };
+
+        Some(frame)
+    }
+}
@@ -0,0 +1,333 @@
+use crate::ffi::mxf::MXFKey;
+use num_rational::Rational32;
+
+/* Local set tags of the index table segment, see SMPTE 377 */
+const TAG_INSTANCE_UID: u16 = 0x3c0a;
+const TAG_INDEX_EDIT_RATE: u16 = 0x3f0b;
+const TAG_INDEX_START_POSITION: u16 = 0x3f0c;
+const TAG_INDEX_DURATION: u16 = 0x3f0d;
+const TAG_EDIT_UNIT_BYTE_COUNT: u16 = 0x3f05;
+const TAG_INDEX_SID: u16 = 0x3f06;
+const TAG_BODY_SID: u16 = 0x3f07;
+const TAG_SLICE_COUNT: u16 = 0x3f08;
+const TAG_POS_TABLE_COUNT: u16 = 0x3f0e;
+const TAG_DELTA_ENTRY_ARRAY: u16 = 0x3f09;
+const TAG_INDEX_ENTRY_ARRAY: u16 = 0x3f0a;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DeltaEntry {
+    pub pos_table_index: i8,
+    pub slice: u8,
+    pub element_delta: u32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IndexEntry {
+    pub temporal_offset: i8,
+    pub key_frame_offset: i8,
+    pub flags: u8,
+    pub stream_offset: u64,
+    pub slice_offsets: Vec<u32>,
+    pub pos_table: Vec<Rational32>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IndexTableSegment {
+    pub index_edit_rate: Option<Rational32>,
+    pub index_start_position: i64,
+    pub index_duration: i64,
+    pub edit_unit_byte_count: u32,
+    pub index_sid: u32,
+    pub body_sid: u32,
+    pub slice_count: u8,
+    pub pos_table_count: u8,
+    pub delta_entries: Vec<DeltaEntry>,
+    pub index_entries: Vec<IndexEntry>,
+}
+
+/* Big-endian reader over a byte slice */
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.data.len() < len {
+            return Err("Index table segment is truncated.".to_string());
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from(bytes[0]) << 8 | u16::from(bytes[1]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(bytes
+            .iter()
+            .fold(0, |value, &byte| value << 8 | u32::from(byte)))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, String> {
+        let bytes = self.take(8)?;
+        Ok(bytes
+            .iter()
+            .fold(0, |value, &byte| value << 8 | u64::from(byte)))
+    }
+
+    fn read_rational(&mut self) -> Result<Rational32, String> {
+        let numerator = self.read_u32()? as i32;
+        let denominator = self.read_u32()? as i32;
+        if denominator == 0 {
+            return Err("Index table segment has a zero denominator.".to_string());
+        }
+        Ok(Rational32::new(numerator, denominator))
+    }
+}
+
+fn write_item(data: &mut Vec<u8>, tag: u16, value: &[u8]) {
+    data.extend_from_slice(&tag.to_be_bytes());
+    data.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    data.extend_from_slice(value);
+}
+
+fn write_batch_header(data: &mut Vec<u8>, count: usize, item_len: usize) {
+    data.extend_from_slice(&(count as u32).to_be_bytes());
+    data.extend_from_slice(&(item_len as u32).to_be_bytes());
+}
+
+impl IndexTableSegment {
+    /* Parses the value of an index table segment KLV */
+    pub fn parse(data: &[u8]) -> Result<IndexTableSegment, String> {
+        let mut segment = IndexTableSegment::default();
+        let mut reader = Reader { data: data };
+        /* The arrays depend on the slice and pos table counts, which may come after them */
+        let mut delta_entry_array = None;
+        let mut index_entry_array = None;
+
+        while !reader.data.is_empty() {
+            let tag = reader.read_u16()?;
+            let len = reader.read_u16()? as usize;
+            let value = reader.take(len)?;
+            let mut item = Reader { data: value };
+            match tag {
+                TAG_INDEX_EDIT_RATE => segment.index_edit_rate = Some(item.read_rational()?),
+                TAG_INDEX_START_POSITION => segment.index_start_position = item.read_u64()? as i64,
+                TAG_INDEX_DURATION => segment.index_duration = item.read_u64()? as i64,
+                TAG_EDIT_UNIT_BYTE_COUNT => segment.edit_unit_byte_count = item.read_u32()?,
+                TAG_INDEX_SID => segment.index_sid = item.read_u32()?,
+                TAG_BODY_SID => segment.body_sid = item.read_u32()?,
+                TAG_SLICE_COUNT => segment.slice_count = item.read_u8()?,
+                TAG_POS_TABLE_COUNT => segment.pos_table_count = item.read_u8()?,
+                TAG_DELTA_ENTRY_ARRAY => delta_entry_array = Some(value),
+                TAG_INDEX_ENTRY_ARRAY => index_entry_array = Some(value),
+                /* Instance UID and extensions */
+                _ => {}
+            }
+        }
+
+        if let Some(value) = delta_entry_array {
+            let mut batch = Reader { data: value };
+            let count = batch.read_u32()?;
+            let item_len = batch.read_u32()? as usize;
+            for _ in 0..count {
+                let mut item = Reader {
+                    data: batch.take(item_len)?,
+                };
+                segment.delta_entries.push(DeltaEntry {
+                    pos_table_index: item.read_u8()? as i8,
+                    slice: item.read_u8()?,
+                    element_delta: item.read_u32()?,
+                });
+            }
+        }
+
+        if let Some(value) = index_entry_array {
+            let mut batch = Reader { data: value };
+            let count = batch.read_u32()?;
+            let item_len = batch.read_u32()? as usize;
+            for _ in 0..count {
+                let mut item = Reader {
+                    data: batch.take(item_len)?,
+                };
+                let mut entry = IndexEntry {
+                    temporal_offset: item.read_u8()? as i8,
+                    key_frame_offset: item.read_u8()? as i8,
+                    flags: item.read_u8()?,
+                    stream_offset: item.read_u64()?,
+                    slice_offsets: Vec::new(),
+                    pos_table: Vec::new(),
+                };
+                for _ in 0..segment.slice_count {
+                    entry.slice_offsets.push(item.read_u32()?);
+                }
+                for _ in 0..segment.pos_table_count {
+                    entry.pos_table.push(item.read_rational()?);
+                }
+                segment.index_entries.push(entry);
+            }
+        }
+
+        Ok(segment)
+    }
+
+    /* Encodes the value of an index table segment KLV */
+    pub fn encode(&self, instance_uid: &MXFKey) -> Vec<u8> {
+        let mut data = Vec::new();
+        write_item(&mut data, TAG_INSTANCE_UID, &instance_uid.as_bytes());
+        if let Some(ref edit_rate) = self.index_edit_rate {
+            let mut value = (*edit_rate.numer() as u32).to_be_bytes().to_vec();
+            value.extend_from_slice(&(*edit_rate.denom() as u32).to_be_bytes());
+            write_item(&mut data, TAG_INDEX_EDIT_RATE, &value);
+        }
+        write_item(
+            &mut data,
+            TAG_INDEX_START_POSITION,
+            &(self.index_start_position as u64).to_be_bytes(),
+        );
+        write_item(
+            &mut data,
+            TAG_INDEX_DURATION,
+            &(self.index_duration as u64).to_be_bytes(),
+        );
+        write_item(
+            &mut data,
+            TAG_EDIT_UNIT_BYTE_COUNT,
+            &self.edit_unit_byte_count.to_be_bytes(),
+        );
+        write_item(&mut data, TAG_INDEX_SID, &self.index_sid.to_be_bytes());
+        write_item(&mut data, TAG_BODY_SID, &self.body_sid.to_be_bytes());
+        write_item(&mut data, TAG_SLICE_COUNT, &[self.slice_count]);
+        write_item(&mut data, TAG_POS_TABLE_COUNT, &[self.pos_table_count]);
+
+        if !self.delta_entries.is_empty() {
+            let mut value = Vec::new();
+            write_batch_header(&mut value, self.delta_entries.len(), 6);
+            for entry in &self.delta_entries {
+                value.push(entry.pos_table_index as u8);
+                value.push(entry.slice);
+                value.extend_from_slice(&entry.element_delta.to_be_bytes());
+            }
+            write_item(&mut data, TAG_DELTA_ENTRY_ARRAY, &value);
+        }
+
+        if !self.index_entries.is_empty() {
+            let item_len = 11 + 4 * self.slice_count as usize + 8 * self.pos_table_count as usize;
+            let mut value = Vec::new();
+            write_batch_header(&mut value, self.index_entries.len(), item_len);
+            for entry in &self.index_entries {
+                value.push(entry.temporal_offset as u8);
+                value.push(entry.key_frame_offset as u8);
+                value.push(entry.flags);
+                value.extend_from_slice(&entry.stream_offset.to_be_bytes());
+                for slice_offset in &entry.slice_offsets {
+                    value.extend_from_slice(&slice_offset.to_be_bytes());
+                }
+                for pos in &entry.pos_table {
+                    value.extend_from_slice(&(*pos.numer() as u32).to_be_bytes());
+                    value.extend_from_slice(&(*pos.denom() as u32).to_be_bytes());
+                }
+            }
+            write_item(&mut data, TAG_INDEX_ENTRY_ARRAY, &value);
+        }
+
+        data
+    }
+
+    fn contains(&self, position: i64) -> bool {
+        let duration = if self.edit_unit_byte_count > 0 {
+            self.index_duration
+        } else {
+            self.index_entries.len() as i64
+        };
+        position >= self.index_start_position
+            && (position < self.index_start_position + duration
+                /* A CBR segment with zero duration covers the whole essence */
+                || (self.edit_unit_byte_count > 0 && duration == 0))
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IndexTable {
+    pub segments: Vec<IndexTableSegment>,
+    /* File offset and length of the essence element the stream offsets refer to */
+    pub essence_offset: u64,
+    pub essence_length: u64,
+}
+
+impl IndexTable {
+    /* Constant bit rate essence has a fixed edit unit byte count and no index entries */
+    pub fn is_cbr(&self) -> bool {
+        !self.segments.is_empty()
+            && self
+                .segments
+                .iter()
+                .all(|segment| segment.edit_unit_byte_count > 0)
+    }
+
+    /* Number of indexed edit units */
+    pub fn duration(&self) -> i64 {
+        self.segments
+            .iter()
+            .map(|segment| {
+                if segment.edit_unit_byte_count > 0 && segment.index_duration == 0 {
+                    /* A CBR segment with zero duration covers the whole essence */
+                    (self.essence_length / u64::from(segment.edit_unit_byte_count)) as i64
+                } else if segment.edit_unit_byte_count > 0 {
+                    segment.index_start_position + segment.index_duration
+                } else {
+                    segment.index_start_position + segment.index_entries.len() as i64
+                }
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn find_segment(&self, position: i64) -> Option<&IndexTableSegment> {
+        self.segments
+            .iter()
+            .find(|segment| segment.contains(position))
+    }
+
+    fn stream_offset(&self, position: i64) -> Option<u64> {
+        let segment = self.find_segment(position)?;
+        segment
+            .index_entries
+            .get((position - segment.index_start_position) as usize)
+            .map(|entry| entry.stream_offset)
+    }
+
+    /* Returns the file offset and size of the edit unit at the position */
+    pub fn frame_offset(&self, position: i64) -> Result<(u64, u32), String> {
+        let segment = match self.find_segment(position) {
+            Some(x) if position >= 0 => x,
+            _ => return Err(format!("Position {} is not indexed.", position)),
+        };
+        let (offset, end) = if segment.edit_unit_byte_count > 0 {
+            let size = u64::from(segment.edit_unit_byte_count);
+            (position as u64 * size, (position as u64 + 1) * size)
+        } else {
+            let offset = self
+                .stream_offset(position)
+                .ok_or(format!("Position {} is not indexed.", position))?;
+            /* The last edit unit runs to the end of the essence */
+            let end = self
+                .stream_offset(position + 1)
+                .unwrap_or(self.essence_length);
+            (offset, end)
+        };
+        if offset >= end || end > self.essence_length {
+            return Err(format!("Position {} is beyond the essence.", position));
+        }
+
+        Ok((self.essence_offset + offset, (end - offset) as u32))
+    }
+}
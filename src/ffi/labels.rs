@@ -0,0 +1,159 @@
+use crate::ffi::mxf::MXFKey;
+
+/* Universal labels hand-picked from the SMPTE Labels register (RP 224) and the Avid AAF
+ * extensions, covering the operational patterns, essence containers, codings and data
+ * definitions found in Avid media. The table is not generated from the register, so labels
+ * missing here are described as unknown. The version octet (octet 7) of SMPTE labels is ignored
+ * when looking labels up, so a single entry covers all registry versions of a label. */
+#[rustfmt::skip]
+pub static MXF_LABELS: &'static [(MXFKey, &'static str)] = &[
+    /* Operational patterns */
+    mxf_label_definition!("OP1a", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x01, 0x01, 0x09, 0x00),
+    mxf_label_definition!("OP1b", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x01, 0x02, 0x09, 0x00),
+    mxf_label_definition!("OP2a", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x02, 0x01, 0x09, 0x00),
+    mxf_label_definition!("OP-Atom", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x02, 0x0d, 0x01, 0x02, 0x01, 0x10, 0x00, 0x00, 0x00),
+    mxf_label_definition!("OP-Atom 1 Track 1 SourceClip", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x02, 0x0d, 0x01, 0x02, 0x01, 0x10, 0x01, 0x00, 0x00),
+    mxf_label_definition!("OP-Atom 1 Track N SourceClips", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x02, 0x0d, 0x01, 0x02, 0x01, 0x10, 0x02, 0x00, 0x00),
+    mxf_label_definition!("OP-Atom N Tracks", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x02, 0x0d, 0x01, 0x02, 0x01, 0x10, 0x03, 0x00, 0x00),
+
+    /* Essence containers */
+    mxf_label_definition!("Generic Container Multiple Mappings", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x03, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x7f, 0x01, 0x00),
+    mxf_label_definition!("D-10 50Mbps 625/50i Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x01, 0x01),
+    mxf_label_definition!("D-10 50Mbps 625/50i Picture-only Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x01, 0x7f),
+    mxf_label_definition!("D-10 50Mbps 525/59.94i Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x02, 0x01),
+    mxf_label_definition!("D-10 50Mbps 525/59.94i Picture-only Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x02, 0x7f),
+    mxf_label_definition!("D-10 40Mbps 625/50i Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x03, 0x01),
+    mxf_label_definition!("D-10 40Mbps 625/50i Picture-only Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x03, 0x7f),
+    mxf_label_definition!("D-10 40Mbps 525/59.94i Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x04, 0x01),
+    mxf_label_definition!("D-10 40Mbps 525/59.94i Picture-only Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x04, 0x7f),
+    mxf_label_definition!("D-10 30Mbps 625/50i Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x05, 0x01),
+    mxf_label_definition!("D-10 30Mbps 625/50i Picture-only Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x05, 0x7f),
+    mxf_label_definition!("D-10 30Mbps 525/59.94i Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x06, 0x01),
+    mxf_label_definition!("D-10 30Mbps 525/59.94i Picture-only Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x01, 0x06, 0x7f),
+    mxf_label_definition!("IEC DV 525/59.94i 25Mbps Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x01, 0x01),
+    mxf_label_definition!("IEC DV 525/59.94i 25Mbps Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x01, 0x02),
+    mxf_label_definition!("IEC DV 625/50i 25Mbps Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x02, 0x01),
+    mxf_label_definition!("IEC DV 625/50i 25Mbps Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x02, 0x02),
+    mxf_label_definition!("DV-based 525/59.94i 25Mbps Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x40, 0x01),
+    mxf_label_definition!("DV-based 525/59.94i 25Mbps Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x40, 0x02),
+    mxf_label_definition!("DV-based 625/50i 25Mbps Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x41, 0x01),
+    mxf_label_definition!("DV-based 625/50i 25Mbps Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x41, 0x02),
+    mxf_label_definition!("DV-based 525/59.94i 50Mbps Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x50, 0x01),
+    mxf_label_definition!("DV-based 525/59.94i 50Mbps Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x50, 0x02),
+    mxf_label_definition!("DV-based 625/50i 50Mbps Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x51, 0x01),
+    mxf_label_definition!("DV-based 625/50i 50Mbps Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x51, 0x02),
+    mxf_label_definition!("DV100 1080/59.94i Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x60, 0x01),
+    mxf_label_definition!("DV100 1080/59.94i Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x60, 0x02),
+    mxf_label_definition!("DV100 1080/50i Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x61, 0x01),
+    mxf_label_definition!("DV100 1080/50i Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x61, 0x02),
+    mxf_label_definition!("DV100 720/59.94p Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x62, 0x01),
+    mxf_label_definition!("DV100 720/59.94p Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x62, 0x02),
+    mxf_label_definition!("DV100 720/50p Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x63, 0x01),
+    mxf_label_definition!("DV100 720/50p Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x63, 0x02),
+    mxf_label_definition!("DV unspecified Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x7f, 0x01),
+    mxf_label_definition!("DV unspecified Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x02, 0x7f, 0x02),
+    mxf_label_definition!("MPEG ES Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x02, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x04, 0x60, 0x01),
+    mxf_label_definition!("MPEG ES Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x02, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x04, 0x60, 0x02),
+    mxf_label_definition!("Uncompressed Picture Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x05, 0x7f, 0x01),
+    mxf_label_definition!("Uncompressed Picture Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x05, 0x7f, 0x02),
+    mxf_label_definition!("BWF Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x06, 0x01, 0x00),
+    mxf_label_definition!("BWF Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x06, 0x02, 0x00),
+    mxf_label_definition!("AES3 Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x06, 0x03, 0x00),
+    mxf_label_definition!("AES3 Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x06, 0x04, 0x00),
+    mxf_label_definition!("JPEG 2000 Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x07, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x0c, 0x01, 0x00),
+    mxf_label_definition!("JPEG 2000 Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x07, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x0c, 0x02, 0x00),
+    mxf_label_definition!("AVC Byte Stream Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x10, 0x60, 0x01),
+    mxf_label_definition!("AVC Byte Stream Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x10, 0x60, 0x02),
+    mxf_label_definition!("VC-3 (DNxHD) Frame-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x11, 0x01, 0x00),
+    mxf_label_definition!("VC-3 (DNxHD) Clip-wrapped", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x11, 0x02, 0x00),
+
+    /* Picture coding */
+    mxf_label_definition!("D-10 50Mbps 625/50i", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x01, 0x02, 0x01, 0x01),
+    mxf_label_definition!("D-10 50Mbps 525/59.94i", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x01, 0x02, 0x01, 0x02),
+    mxf_label_definition!("D-10 40Mbps 625/50i", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x01, 0x02, 0x01, 0x03),
+    mxf_label_definition!("D-10 40Mbps 525/59.94i", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x01, 0x02, 0x01, 0x04),
+    mxf_label_definition!("D-10 30Mbps 625/50i", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x01, 0x02, 0x01, 0x05),
+    mxf_label_definition!("D-10 30Mbps 525/59.94i", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x01, 0x02, 0x01, 0x06),
+    mxf_label_definition!("IEC DV 525/59.94i 25Mbps", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x02, 0x01, 0x01, 0x00),
+    mxf_label_definition!("IEC DV 625/50i 25Mbps", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x02, 0x01, 0x02, 0x00),
+    mxf_label_definition!("DV-based 525/59.94i 25Mbps", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x02, 0x02, 0x01, 0x00),
+    mxf_label_definition!("DV-based 625/50i 25Mbps", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x02, 0x02, 0x02, 0x00),
+    mxf_label_definition!("DV-based 525/59.94i 50Mbps", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x02, 0x02, 0x03, 0x00),
+    mxf_label_definition!("DV-based 625/50i 50Mbps", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x02, 0x02, 0x04, 0x00),
+    mxf_label_definition!("DV100 1080/59.94i", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x02, 0x02, 0x05, 0x00),
+    mxf_label_definition!("DV100 1080/50i", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x02, 0x02, 0x06, 0x00),
+    mxf_label_definition!("DV100 720/59.94p", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x02, 0x02, 0x07, 0x00),
+    mxf_label_definition!("DV100 720/50p", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x02, 0x02, 0x02, 0x08, 0x00),
+    mxf_label_definition!("Uncompressed Picture", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x01, 0x02, 0x01, 0x7f, 0x00, 0x00, 0x00),
+    mxf_label_definition!("JPEG 2000", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x07, 0x04, 0x01, 0x02, 0x02, 0x03, 0x01, 0x01, 0x00),
+    mxf_label_definition!("DNxHD 1235", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x01, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1237", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x03, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1238", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x04, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1241", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x07, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1242", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x08, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1243", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x09, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1244", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x0a, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1250", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x10, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1251", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x11, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1252", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x12, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1253", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x13, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1256", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x16, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1258", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x18, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1259", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x19, 0x00, 0x00),
+    mxf_label_definition!("DNxHD 1260", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x1a, 0x00, 0x00),
+    mxf_label_definition!("AVC-Intra 50 1080i 59.94", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x21, 0x01),
+    mxf_label_definition!("AVC-Intra 50 1080i 50", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x21, 0x02),
+    mxf_label_definition!("AVC-Intra 50 1080p 29.97", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x21, 0x03),
+    mxf_label_definition!("AVC-Intra 50 1080p 25", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x21, 0x04),
+    mxf_label_definition!("AVC-Intra 50 720p 59.94", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x21, 0x08),
+    mxf_label_definition!("AVC-Intra 50 720p 50", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x21, 0x09),
+    mxf_label_definition!("AVC-Intra 100 1080i 59.94", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x31, 0x01),
+    mxf_label_definition!("AVC-Intra 100 1080i 50", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x31, 0x02),
+    mxf_label_definition!("AVC-Intra 100 1080p 29.97", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x31, 0x03),
+    mxf_label_definition!("AVC-Intra 100 1080p 25", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x31, 0x04),
+    mxf_label_definition!("AVC-Intra 100 720p 59.94", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x31, 0x08),
+    mxf_label_definition!("AVC-Intra 100 720p 50", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x01, 0x32, 0x31, 0x09),
+
+    /* Sound coding */
+    mxf_label_definition!("Uncompressed Sound", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x04, 0x02, 0x02, 0x01, 0x7f, 0x00, 0x00, 0x00),
+
+    /* Data definitions */
+    mxf_label_definition!("Picture Essence Track", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x01, 0x03, 0x02, 0x02, 0x01, 0x00, 0x00, 0x00),
+    mxf_label_definition!("Sound Essence Track", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x01, 0x03, 0x02, 0x02, 0x02, 0x00, 0x00, 0x00),
+    mxf_label_definition!("Data Essence Track", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x01, 0x03, 0x02, 0x02, 0x03, 0x00, 0x00, 0x00),
+    mxf_label_definition!("SMPTE 12M Timecode Track", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x01, 0x03, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00),
+    mxf_label_definition!("Descriptive Metadata Track", 0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x01, 0x03, 0x02, 0x01, 0x10, 0x00, 0x00, 0x00),
+
+    /* Avid legacy data definitions */
+    mxf_label_definition!("Avid Legacy Picture", 0x80, 0x7d, 0x00, 0x60, 0x08, 0x14, 0x3e, 0x6f, 0x6f, 0x3c, 0x8c, 0xe1, 0x6c, 0xef, 0x11, 0xd2),
+    mxf_label_definition!("Avid Legacy Sound", 0x80, 0x7d, 0x00, 0x60, 0x08, 0x14, 0x3e, 0x6f, 0x78, 0xe1, 0xeb, 0xe1, 0x6c, 0xef, 0x11, 0xd2),
+    mxf_label_definition!("Avid Legacy Timecode", 0x80, 0x7f, 0x00, 0x60, 0x08, 0x14, 0x3e, 0x6f, 0x7f, 0x27, 0x5e, 0x81, 0x77, 0xe5, 0x11, 0xd2),
+];
+
+pub fn label_name(key: &MXFKey) -> Option<&'static str> {
+    MXF_LABELS
+        .iter()
+        .find(|&&(ref label, _)| label.eq_ignore_version(key))
+        .map(|&(_, name)| name)
+}
+
+pub fn label_by_name(name: &str) -> Option<MXFKey> {
+    MXF_LABELS
+        .iter()
+        .find(|&&(_, label_name)| label_name.eq_ignore_ascii_case(name))
+        .map(|&(label, _)| label)
+}
+
+pub fn label_kind(key: &MXFKey) -> &'static str {
+    match (key.octet4, key.octet8, key.octet9, key.octet10, key.octet11) {
+        (0x04, 0x0d, 0x01, 0x02, 0x01) => "operational pattern",
+        (0x04, 0x0d, 0x01, 0x03, 0x01) => "essence container",
+        (0x04, 0x04, 0x01, 0x02, _) => "picture coding",
+        (0x04, 0x04, 0x02, 0x02, _) => "sound coding",
+        (0x04, 0x01, 0x03, 0x02, _) => "data definition",
+        (0x04, _, _, _, _) => "label",
+        (0x02, _, _, _, _) => "set or pack key",
+        (0x01, _, _, _, _) => "item key",
+        _ => "key",
+    }
+}
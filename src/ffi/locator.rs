@@ -0,0 +1,131 @@
+use crate::ffi::consts::*;
+use crate::ffi::mxf::*;
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocatorKind {
+    Network,
+    Text,
+}
+
+impl fmt::Display for LocatorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LocatorKind::Network => write!(f, "Network"),
+            LocatorKind::Text => write!(f, "Text"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locator {
+    /* Package whose descriptor holds the locator */
+    pub package_uid: Option<MXFUmid>,
+    pub kind: LocatorKind,
+    /* URL string of a network locator, or name of a text locator */
+    pub value: String,
+}
+
+impl Locator {
+    /* Converts a file:// network locator into a local path. Avid writes Windows paths as
+     * file:///C:/path and escapes reserved characters with %XX. */
+    pub fn to_path(&self) -> Option<PathBuf> {
+        if self.kind != LocatorKind::Network {
+            return None;
+        }
+        let rest = strip_prefix_ignore_case(&self.value, "file://");
+        if rest.len() == self.value.len() {
+            return None;
+        }
+        let rest = strip_prefix_ignore_case(rest, "localhost");
+        if !rest.starts_with('/') {
+            /* Remote host */
+            return None;
+        }
+
+        let path = percent_decode(rest)?;
+        let bytes = path.as_bytes();
+        if bytes.len() >= 3
+            && bytes[0] == b'/'
+            && bytes[1].is_ascii_alphabetic()
+            && bytes[2] == b':'
+        {
+            /* Drop the leading slash of a drive letter path */
+            return Some(PathBuf::from(&path[1..]));
+        }
+
+        Some(PathBuf::from(path))
+    }
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+pub fn read_locators(
+    file: &MXFFile,
+    package_set: &mut MXFMetadataSet,
+    descriptor_set: &mut MXFMetadataSet,
+) -> Result<Vec<Locator>, String> {
+    let mut locators = Vec::new();
+    if !descriptor_set.has_item(&G_GENERICDESCRIPTOR_LOCATORS_ITEM_KEY) {
+        return Ok(locators);
+    }
+
+    let package_uid = package_set.get_umid(&G_GENERICPACKAGE_PACKAGEUID_ITEM_KEY);
+    let array_iter = descriptor_set
+        .initialize_array_iterator(&G_GENERICDESCRIPTOR_LOCATORS_ITEM_KEY)
+        .ok_or("Could not read array item iterator.".to_string())?;
+    for item in array_iter {
+        let locator_set = match file.headerdata().get_strongref(item) {
+            Some(x) => x,
+            /* Dark set not registered in dictionary. */
+            None => continue,
+        };
+        let (kind, value) = if file
+            .headerdata()
+            .datamodel()
+            .is_subclass_of(&locator_set.key, &G_NETWORKLOCATOR_SET_KEY)
+        {
+            (
+                LocatorKind::Network,
+                locator_set.get_string(&G_NETWORKLOCATOR_URLSTRING_ITEM_KEY),
+            )
+        } else if file
+            .headerdata()
+            .datamodel()
+            .is_subclass_of(&locator_set.key, &G_TEXTLOCATOR_SET_KEY)
+        {
+            (
+                LocatorKind::Text,
+                locator_set.get_string(&G_TEXTLOCATOR_LOCATORNAME_ITEM_KEY),
+            )
+        } else {
+            continue;
+        };
+        if let Some(value) = value {
+            locators.push(Locator {
+                package_uid: package_uid,
+                kind: kind,
+                value: value,
+            });
+        }
+    }
+
+    Ok(locators)
+}
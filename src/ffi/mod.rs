@@ -1,13 +1,50 @@
 #![allow(non_camel_case_types)]
+mod archive;
+#[cfg(feature = "tokio")]
+mod async_io;
+mod builder;
 mod consts;
+mod dark;
+mod dictionary;
+mod dump;
+mod essence;
+mod index;
+mod labels;
+mod locator;
 mod mxf;
+mod probe;
+mod source_chain;
+mod wav;
+mod writer;
 
+pub use crate::ffi::archive::{
+    ArchiveMetadata, DigiBetaDropout, InfaxRecord, PSEFailure, TimecodeBreak, VTRError,
+};
+pub use crate::ffi::builder::OpAtomBuilder;
+use crate::ffi::consts::*;
+pub use crate::ffi::dark::{DarkItem, DarkMetadata, DarkSet};
+pub use crate::ffi::dictionary::{
+    ClassDefinition, Definition, Dictionary, MetaDictionary, PropertyDefinition,
+};
+pub use crate::ffi::dump::{DumpItem, DumpSet, DumpValue, MetadataDump};
+pub use crate::ffi::essence::{EssenceFrame, EssenceReader};
+pub use crate::ffi::index::{DeltaEntry, IndexEntry, IndexTable, IndexTableSegment};
+use crate::ffi::locator::read_locators;
+pub use crate::ffi::locator::{Locator, LocatorKind};
+use crate::ffi::mxf::*;
+pub use crate::ffi::mxf::{
+    DataModel, MXFFile, MXFKey, MXFUmid, MetadataSource, PartitionInfo, PartitionKind,
+};
+pub use crate::ffi::probe::ProbeInfo;
+use crate::ffi::source_chain::read_source_chain;
+pub use crate::ffi::source_chain::{PackageDescriptorType, SourcePackageHop};
+pub use crate::ffi::writer::MetadataUpdate;
 use chrono::NaiveDateTime;
-use ffi::consts::*;
-use ffi::mxf::*;
-pub use ffi::mxf::{MXFKey, MXFUmid};
 use num_rational::Rational32;
+use std::fmt;
+use std::io::Read;
 use std::path::Path;
+use std::sync::Arc;
 
 fn convert_length(target_edit_rate: &Rational32, edit_rate: &Rational32, length: int64) -> int64 {
     ((length * target_edit_rate.numer().clone() as i64 * edit_rate.denom().clone() as i64) as f64
@@ -24,6 +61,125 @@ fn compare_length(
     length_a - convert_length(edit_rate_a, edit_rate_b, length_b)
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RGBColor {
+    pub red: u16,
+    pub green: u16,
+    pub blue: u16,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Marker {
+    /* Position on the event track in event edit rate units */
+    pub position: i64,
+    /* Position in clip edit rate units including the start timecode */
+    pub timecode: i64,
+    pub comment: Option<String>,
+    pub color: Option<RGBColor>,
+    pub track_ids: Vec<u32>,
+}
+
+/* Start timecode of the physical source package as a frame count at the rounded timecode
+ * base, e.g. 30 for 29.97 fps */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Timecode {
+    pub frames: i64,
+    pub rounded_base: u16,
+    pub drop_frame: bool,
+}
+
+impl fmt::Display for Timecode {
+    /* HH:MM:SS:FF, or HH:MM:SS;FF with the frame numbers dropped at the start of each minute
+     * except every tenth for drop frame timecode, see SMPTE 12M */
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let base = i64::from(self.rounded_base.max(1));
+        let mut frames = self.frames.max(0);
+        let is_drop_frame = self.drop_frame && base % 30 == 0;
+        if is_drop_frame {
+            let dropped = base / 15;
+            let frames_per_minute = base * 60 - dropped;
+            let frames_per_ten_minutes = base * 600 - dropped * 9;
+            let ten_minutes = frames / frames_per_ten_minutes;
+            let remainder = frames % frames_per_ten_minutes;
+            frames += dropped * 9 * ten_minutes;
+            if remainder > dropped {
+                frames += dropped * ((remainder - dropped) / frames_per_minute);
+            }
+        }
+
+        write!(
+            f,
+            "{:02}:{:02}:{:02}{}{:02}",
+            frames / (base * 3600),
+            frames / (base * 60) % 60,
+            frames / base % 60,
+            if is_drop_frame { ';' } else { ':' },
+            frames % base
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EssenceGroupChoice {
+    pub source_package_uid: MXFUmid,
+    pub source_track_id: Option<u32>,
+    pub start_position: Option<i64>,
+    pub duration: Option<i64>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EssenceGroup {
+    pub essence_group_type: Option<u32>,
+    pub choices: Vec<EssenceGroupChoice>,
+    pub still_frame: Option<EssenceGroupChoice>,
+}
+
+fn read_essence_group_choice(set: &mut MXFMetadataSet) -> Result<EssenceGroupChoice, String> {
+    Ok(EssenceGroupChoice {
+        source_package_uid: set
+            .get_umid(&G_SOURCECLIP_SOURCEPACKAGEID_ITEM_KEY)
+            .ok_or("Could not read source package id.".to_string())?,
+        source_track_id: set.get_uint32(&G_SOURCECLIP_SOURCETRACKID_ITEM_KEY),
+        start_position: set.get_position(&G_SOURCECLIP_STARTPOSITION_ITEM_KEY),
+        duration: set.get_length(&G_STRUCTURALCOMPONENT_DURATION_ITEM_KEY),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Captured,
+    Imported,
+    Consolidated,
+    Transcoded,
+    VideoRender,
+    AudioRender,
+    Unknown,
+}
+
+impl MediaKind {
+    /* Render files can be recreated by the editor and are safe to delete */
+    pub fn is_render(&self) -> bool {
+        match *self {
+            MediaKind::VideoRender | MediaKind::AudioRender => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for MediaKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MediaKind::Captured => write!(f, "Captured"),
+            MediaKind::Imported => write!(f, "Imported"),
+            MediaKind::Consolidated => write!(f, "Consolidated"),
+            MediaKind::Transcoded => write!(f, "Transcoded"),
+            MediaKind::VideoRender => write!(f, "Video Render"),
+            MediaKind::AudioRender => write!(f, "Audio Render"),
+            MediaKind::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct AvidMXFInfo {
     /* String values */
@@ -32,12 +188,15 @@ pub struct AvidMXFInfo {
     pub track_string: Option<String>,
     pub physical_package_name: Option<String>,
     pub physical_package_locator: Option<String>,
+    pub locators: Vec<Locator>,
+    pub codec_name: Option<String>,
 
     /* Rational value */
     pub project_edit_rate: Option<Rational32>,
     pub clip_edit_rate: Option<Rational32>,
     pub aspect_ratio: Option<Rational32>,
     pub audio_sampling_rate: Option<Rational32>,
+    pub sample_rate: Option<Rational32>,
 
     /* UMID */
     pub file_source_package_uid: Option<MXFUmid>,
@@ -47,6 +206,10 @@ pub struct AvidMXFInfo {
     /* UL */
     pub essence_container_label: Option<MXFKey>,
     pub picture_coding_label: Option<MXFKey>,
+    pub descriptor_essence_container_label: Option<MXFKey>,
+    pub codec_label: Option<MXFKey>,
+    pub essence_container_labels: Vec<MXFKey>,
+    pub essence_container_mismatch: bool,
 
     /* Timstamps */
     pub clip_created: Option<NaiveDateTime>,
@@ -66,26 +229,110 @@ pub struct AvidMXFInfo {
     pub track_duration: Option<i64>,
     pub segment_duration: Option<i64>,
     pub start_timecode: i64,
+    pub timecode: Option<Timecode>,
     pub track_number: Option<u32>,
     pub channel_count: Option<u32>,
+    pub block_align: Option<u16>,
     pub quantization_bits: Option<u32>,
     pub clip_duration: Option<i64>,
+    pub container_duration: Option<i64>,
     pub is_video: bool,
     pub audio_track_count: u32,
     pub video_track_count: u32,
     pub avid_resolution_id: Option<i32>,
+
+    /* Essence group of the material track referencing the file source package */
+    pub essence_group: Option<EssenceGroup>,
+
+    /* Partitions */
+    pub header_closed: bool,
+    pub header_complete: bool,
+    pub footer_partition_offset: Option<u64>,
+    pub metadata_partition_offset: u64,
+
+    /* Application specific package values */
+    pub app_code: Option<i32>,
+    pub mob_attributes: Vec<(String, String)>,
+
+    /* Derivation chain from the material package down to the original source */
+    pub source_chain: Vec<SourcePackageHop>,
+
+    /* Editor markers */
+    pub markers: Vec<Marker>,
 }
 
 impl AvidMXFInfo {
     pub fn from_file(filename: &Path) -> Result<AvidMXFInfo, String> {
+        AvidMXFInfo::from_file_with_metadata(filename, MetadataSource::Header)
+    }
+
+    pub fn from_file_with_metadata(
+        filename: &Path,
+        source: MetadataSource,
+    ) -> Result<AvidMXFInfo, String> {
+        let file = MXFFile::open_with_metadata(filename, true, source)?;
+        AvidMXFInfo::from_mxf_file(&file)
+    }
+
+    /* Sets and items the data model does not know, such as vendor extensions. Read on request
+     * since it parses the header metadata a second time. */
+    pub fn read_dark_metadata(filename: &Path) -> Result<DarkMetadata, String> {
+        let file = MXFFile::open(filename, true)?;
+        file.dark_metadata()
+    }
+
+    /* Reads the info with a data model shared between files */
+    pub fn from_file_with_model(
+        filename: &Path,
+        source: MetadataSource,
+        model: &Arc<DataModel>,
+    ) -> Result<AvidMXFInfo, String> {
+        let file = MXFFile::open_with_model(filename, true, source, model)?;
+        AvidMXFInfo::from_mxf_file(&file)
+    }
+
+    /* Reads the info from the start of a file held in memory, see header_metadata_size */
+    pub fn from_memory(data: Vec<u8>, model: &Arc<DataModel>) -> Result<AvidMXFInfo, String> {
+        let file = MXFFile::from_memory(data, true, model)?;
+        AvidMXFInfo::from_mxf_file(&file)
+    }
+
+    /* Reads the header partition into memory and parses it there. Only the start of the file
+     * is read, so footer partition status is not available. */
+    pub fn from_reader<R: Read>(
+        reader: &mut R,
+        model: &Arc<DataModel>,
+    ) -> Result<AvidMXFInfo, String> {
+        let mut data = vec![0; MXFFile::HEADER_PREFIX_SIZE];
+        reader
+            .read_exact(&mut data)
+            .map_err(|e| format!("Could not read header: {}.", e))?;
+        let size = MXFFile::header_metadata_size(&data)? as usize;
+        if size <= data.len() {
+            data.truncate(size);
+        } else {
+            let start = data.len();
+            data.resize(size, 0);
+            reader
+                .read_exact(&mut data[start..])
+                .map_err(|e| format!("Could not read header metadata: {}.", e))?;
+        }
+
+        AvidMXFInfo::from_memory(data, model)
+    }
+
+    fn from_mxf_file(file: &MXFFile) -> Result<AvidMXFInfo, String> {
         let mut info = AvidMXFInfo::default();
         let mut max_duration = 0;
         let mut max_edit_rate = Rational32::new(25, 1);
         let mut package_uid = MXFUmid::default();
         let mob_name = vec![95, 80, 74, 0];
 
-        /* Open file */
-        let file = MXFFile::from_file(filename)?;
+        /* Get the partition status */
+        info.header_closed = file.headerpartition().is_closed();
+        info.header_complete = file.headerpartition().is_complete();
+        info.footer_partition_offset = file.footer_partition_offset();
+        info.metadata_partition_offset = file.metadatapartition().this_partition;
 
         /* Get preface set */
         let preface_set = file
@@ -102,10 +349,35 @@ impl AvidMXFInfo {
             info.project_edit_rate = preface_set.get_rational(&G_PREFACE_PROJECTEDITRATE_ITEM_KEY);
         }
 
-        /* Get essence container label */
-        info.essence_container_label = Some(MXFKey::from(
-            file.headerpartition().essence_containers().at(0),
-        ));
+        /* Get essence container labels from the header partition pack and the preface */
+        let partition_labels: Vec<MXFKey> = file
+            .headerpartition()
+            .essence_containers()
+            .get_iter()
+            .map(MXFKey::from)
+            .collect();
+        let mut preface_labels = Vec::new();
+        let has_preface_labels = preface_set.has_item(&G_PREFACE_ESSENCECONTAINERS_ITEM_KEY);
+        if has_preface_labels {
+            let array_iter = preface_set
+                .initialize_array_iterator(&G_PREFACE_ESSENCECONTAINERS_ITEM_KEY)
+                .ok_or("Could not read preface essence containers.".to_string())?;
+            for item in array_iter {
+                preface_labels.push(MXFKey::from(item as *mut uint8 as *mut c_void));
+            }
+        }
+        /* The preface item is optional, only labels that are present can disagree */
+        info.essence_container_mismatch = has_preface_labels
+            && (partition_labels.len() != preface_labels.len()
+                || partition_labels
+                    .iter()
+                    .any(|label| !preface_labels.contains(label)));
+        for label in partition_labels.into_iter().chain(preface_labels) {
+            if !info.essence_container_labels.contains(&label) {
+                info.essence_container_labels.push(label);
+            }
+        }
+        info.essence_container_label = info.essence_container_labels.first().cloned();
 
         /* Get material package set */
         let material_package_set = file
@@ -121,6 +393,15 @@ impl AvidMXFInfo {
                 material_package_set.get_timestamp(&G_GENERICPACKAGE_PACKAGECREATIONDATE_ITEM_KEY);
         }
 
+        if material_package_set.has_item(&G_GENERICPACKAGE_APPCODE_ITEM_KEY) {
+            info.app_code = material_package_set.get_int32(&G_GENERICPACKAGE_APPCODE_ITEM_KEY);
+        }
+        if material_package_set.has_item(&G_GENERICPACKAGE_MOBATTRIBUTELIST_ITEM_KEY) {
+            info.mob_attributes = material_package_set
+                .get_mob_attributes()
+                .ok_or("Could not read mob attributes.".to_string())?;
+        }
+
         /* Get the material package project name tagged value if not aleady set */
         if info.project_name.is_none()
             && material_package_set.has_item(&G_GENERICPACKAGE_MOBATTRIBUTELIST_ITEM_KEY)
@@ -144,6 +425,34 @@ impl AvidMXFInfo {
         let descriptor_set = file_source_package_set
             .get_strongref(&G_SOURCEPACKAGE_DESCRIPTOR_ITEM_KEY)
             .ok_or("Could not read descriptor set.".to_string())?;
+        if file
+            .datamodel()
+            .is_subclass_of(&descriptor_set.key, &G_FILEDESCRIPTOR_SET_KEY)
+        {
+            /* Sample rate */
+            if descriptor_set.has_item(&G_FILEDESCRIPTOR_SAMPLERATE_ITEM_KEY) {
+                info.sample_rate =
+                    descriptor_set.get_rational(&G_FILEDESCRIPTOR_SAMPLERATE_ITEM_KEY);
+            }
+            /* Container duration */
+            if descriptor_set.has_item(&G_FILEDESCRIPTOR_CONTAINERDURATION_ITEM_KEY) {
+                info.container_duration =
+                    descriptor_set.get_length(&G_FILEDESCRIPTOR_CONTAINERDURATION_ITEM_KEY);
+            }
+            /* Essence container label */
+            if descriptor_set.has_item(&G_FILEDESCRIPTOR_ESSENCECONTAINER_ITEM_KEY) {
+                info.descriptor_essence_container_label =
+                    descriptor_set.get_ul(&G_FILEDESCRIPTOR_ESSENCECONTAINER_ITEM_KEY);
+            }
+            /* Codec is a weak reference to a codec definition in the dictionary */
+            if descriptor_set.has_item(&G_FILEDESCRIPTOR_CODEC_ITEM_KEY) {
+                info.codec_label = descriptor_set.get_ul(&G_FILEDESCRIPTOR_CODEC_ITEM_KEY);
+                info.codec_name = match info.codec_label {
+                    Some(ref codec) => file.headerdata().get_codec_def_name(codec),
+                    None => None,
+                };
+            }
+        }
         if file.datamodel().is_subclass_of(
             &descriptor_set.key,
             &G_GENERICPICTUREESSENCEDESCRIPTOR_SET_KEY,
@@ -200,6 +509,11 @@ impl AvidMXFInfo {
                 info.channel_count = descriptor_set
                     .get_uint32(&G_GENERICSOUNDESSENCEDESCRIPTOR_CHANNELCOUNT_ITEM_KEY);
             }
+            /* Block align of BWF and AES3 audio */
+            if descriptor_set.has_item(&G_WAVEAUDIODESCRIPTOR_BLOCKALIGN_ITEM_KEY) {
+                info.block_align =
+                    descriptor_set.get_uint16(&G_WAVEAUDIODESCRIPTOR_BLOCKALIGN_ITEM_KEY);
+            }
         }
 
         /* Get the material track referencing the file source package and info */
@@ -260,6 +574,7 @@ impl AvidMXFInfo {
             }
             /* Get info from this track if it refrences the file source package through a
              * source clip */
+            let mut essence_group = None;
             let sequence_set = track_set
                 .get_strongref(&G_GENERICTRACK_SEQUENCE_ITEM_KEY)
                 .ok_or("Could not read generic track sequences.".to_string())?;
@@ -293,9 +608,27 @@ impl AvidMXFInfo {
                             .ok_or(
                                 "Could not read array essence group choices count.".to_string(),
                             )?;
-                        let mut final_idx = 0;
+                        let mut group = EssenceGroup::default();
+                        if source_clip_set.has_item(&G_ESSENCEGROUP_ESSENCEGROUPTYPE_ITEM_KEY) {
+                            group.essence_group_type = source_clip_set
+                                .get_item_value(&G_ESSENCEGROUP_ESSENCEGROUPTYPE_ITEM_KEY)
+                                .map(|value| {
+                                    value.iter().fold(0, |acc, &octet| acc << 8 | octet as u32)
+                                });
+                        }
+                        if source_clip_set.has_item(&G_ESSENCEGROUP_STILLFRAME_ITEM_KEY) {
+                            group.still_frame = match source_clip_set
+                                .get_strongref(&G_ESSENCEGROUP_STILLFRAME_ITEM_KEY)
+                            {
+                                Some(still_frame_set) => {
+                                    Some(read_essence_group_choice(still_frame_set)?)
+                                }
+                                /* Dark set not registered in dictionary. */
+                                None => None,
+                            };
+                        }
+                        let mut found = false;
                         for j in 0..choices_count {
-                            final_idx = j;
                             let elem = source_clip_set
                                 .get_array_element(&G_ESSENCEGROUP_CHOICES_ITEM_KEY, j)
                                 .ok_or("Could not read essence group choices.".to_string())?;
@@ -312,16 +645,18 @@ impl AvidMXFInfo {
                                 .datamodel()
                                 .is_subclass_of(&source_clip_set.key, &G_SOURCECLIP_SET_KEY)
                             {
-                                package_uid = source_clip_set
-                                    .get_umid(&G_SOURCECLIP_SOURCEPACKAGEID_ITEM_KEY)
-                                    .ok_or("Could not read source package id.".to_string())?;
-                                if &package_uid == info.file_source_package_uid.as_ref().unwrap() {
+                                let choice = read_essence_group_choice(source_clip_set)?;
+                                if !found {
+                                    package_uid = choice.source_package_uid;
                                     /* Found source clip referencing file source package */
-                                    break;
+                                    found = &package_uid
+                                        == info.file_source_package_uid.as_ref().unwrap();
                                 }
+                                group.choices.push(choice);
                             }
                         }
-                        if final_idx < choices_count {
+                        essence_group = Some(group);
+                        if choices_count > 0 {
                             /* Found source clip referencing source package */
                             break;
                         }
@@ -352,6 +687,7 @@ impl AvidMXFInfo {
                 info.clip_edit_rate = edit_rate;
                 info.track_duration = Some(track_duration);
                 info.track_number = Some(track_number);
+                info.essence_group = essence_group;
             }
         }
 
@@ -381,33 +717,17 @@ impl AvidMXFInfo {
                 None => continue,
             };
 
-            /* Get first physical network locator */
-            if descriptor_set.has_item(&G_GENERICDESCRIPTOR_LOCATORS_ITEM_KEY) {
-                let mut array_iter = descriptor_set
-                    .initialize_array_iterator(&G_GENERICDESCRIPTOR_LOCATORS_ITEM_KEY)
-                    .ok_or("Could not read array item iterator.".to_string())?;
-                for item in array_iter {
-                    let locator_set = file
-                        .headerdata()
-                        .get_strongref(item)
-                        .ok_or("Could not read locator set.")?;
-                    if file
-                        .headerdata()
-                        .datamodel()
-                        .is_subclass_of(&locator_set.key, &G_NETWORKLOCATOR_SET_KEY)
-                    {
-                        info.physical_package_locator =
-                            locator_set.get_string(&G_NETWORKLOCATOR_URLSTRING_ITEM_KEY);
-                    }
-                }
-            }
+            /* Get the network and text locators of every source package */
+            info.locators
+                .extend(read_locators(file, set, descriptor_set)?);
 
             /* NOTE: Some descriptors could be dark and so we don't assume we can dereference
              * */
-            if file
-                .headerdata()
-                .datamodel()
-                .is_subclass_of(&descriptor_set.key, &G_PHYSICALDESCRIPTOR_SET_KEY)
+            if info.physical_source_package_uid.is_none()
+                && file
+                    .headerdata()
+                    .datamodel()
+                    .is_subclass_of(&descriptor_set.key, &G_PHYSICALDESCRIPTOR_SET_KEY)
             {
                 if file
                     .headerdata()
@@ -436,12 +756,25 @@ impl AvidMXFInfo {
                 if set.has_item(&G_GENERICPACKAGE_NAME_ITEM_KEY) {
                     info.physical_package_name = set.get_string(&G_GENERICPACKAGE_NAME_ITEM_KEY);
                 }
-
-                break;
             }
         }
         MXFList::free(&mut list);
 
+        /* Prefer the first network locator of the physical package */
+        info.physical_package_locator = info
+            .locators
+            .iter()
+            .filter(|locator| locator.kind == LocatorKind::Network)
+            .find(|locator| {
+                info.physical_source_package_uid.is_none()
+                    || locator.package_uid == info.physical_source_package_uid
+            })
+            .or(info
+                .locators
+                .iter()
+                .find(|locator| locator.kind == LocatorKind::Network))
+            .map(|locator| locator.value.clone());
+
         /* Get the start timecode
          * the source timecode is calculated using the SourceClip::start_position in the file
          * source package in conjunction with the TimecodeComponent in the referenced physical
@@ -533,6 +866,9 @@ impl AvidMXFInfo {
                 let mut timecode_base = timecode_component_set
                     .get_uint16(&G_TIMECODECOMPONENT_ROUNDEDTIMECODEBASE_ITEM_KEY)
                     .ok_or("Could not read timecode base item.".to_string())?;
+                let drop_frame = timecode_component_set
+                    .get_boolean(&G_TIMECODECOMPONENT_DROPFRAME_ITEM_KEY)
+                    .unwrap_or(false);
                 if info.clip_edit_rate.is_none() || package_edit_rate.is_none() {
                     return Err("Clip edit rate is empty for timecode calculation.".to_string());
                 }
@@ -540,10 +876,10 @@ impl AvidMXFInfo {
                 let p_edit_rate = package_edit_rate.as_ref().unwrap();
 
                 let mut start_pos = package_start_pos;
+                let tmp_timecode_base = (p_edit_rate.numer().clone() as f64
+                    / p_edit_rate.denom().clone() as f64
+                    + 0.5) as u16;
                 if start_timecode > 0 {
-                    let tmp_timecode_base = (p_edit_rate.numer().clone() as f64
-                        / p_edit_rate.denom().clone() as f64
-                        + 0.5) as u16;
                     if tmp_timecode_base == timecode_base {
                         start_pos += start_timecode;
                     } else if tmp_timecode_base == 2 * timecode_base {
@@ -552,6 +888,17 @@ impl AvidMXFInfo {
                         /* TODO: Complete support for different timecode and edit rates */
                     }
                 }
+                /* Timecode frames of the package start position */
+                let package_start_frames = if tmp_timecode_base == 2 * timecode_base {
+                    package_start_pos / 2
+                } else {
+                    package_start_pos
+                };
+                info.timecode = Some(Timecode {
+                    frames: start_timecode + package_start_frames,
+                    rounded_base: timecode_base,
+                    drop_frame: drop_frame,
+                });
 
                 info.start_timecode = ((start_pos
                     * c_edit_rate.numer().clone() as i64
@@ -565,10 +912,196 @@ impl AvidMXFInfo {
             }
         }
 
+        /* Walk the source package derivation chain */
+        info.source_chain = read_source_chain(
+            &file,
+            material_package_set,
+            info.file_source_package_uid.as_ref(),
+        );
+
+        /* Get the editor markers
+         * Avid stores locators as DM segments in the sequence of an event track in the material
+         * package */
+        let mut array_iter = material_package_set
+            .get_package_tracks()
+            .ok_or("Could not read package tracks.".to_string())?;
+        array_iter.headerdata = Some(file.headerdata());
+        for track_set in array_iter {
+            if !file
+                .datamodel()
+                .is_subclass_of(&track_set.key, &G_EVENTTRACK_SET_KEY)
+            {
+                continue;
+            }
+            let event_edit_rate = track_set.get_rational(&G_EVENTTRACK_EVENTEDITRATE_ITEM_KEY);
+            let event_origin = track_set
+                .get_position(&G_EVENTTRACK_EVENTORIGIN_ITEM_KEY)
+                .unwrap_or(0);
+            let sequence_set = match track_set.get_strongref(&G_GENERICTRACK_SEQUENCE_ITEM_KEY) {
+                Some(x) => x,
+                None => continue,
+            };
+
+            let mut segment_sets = Vec::new();
+            if sequence_set.is_subclass_of(&G_SEQUENCE_SET_KEY) {
+                let array_iter = sequence_set
+                    .initialize_array_iterator(&G_SEQUENCE_STRUCTURALCOMPONENTS_ITEM_KEY)
+                    .ok_or("Could not read event track structural components.".to_string())?;
+                for item in array_iter {
+                    match file.headerdata().get_strongref(item) {
+                        Some(set) => segment_sets.push(set),
+                        /* Dark set not registered in dictionary. */
+                        None => continue,
+                    }
+                }
+            } else {
+                segment_sets.push(sequence_set);
+            }
+
+            for segment_set in segment_sets {
+                if !segment_set.is_subclass_of(&G_DMSEGMENT_SET_KEY)
+                    || !segment_set.has_item(&G_DMSEGMENT_EVENTSTARTPOSITION_ITEM_KEY)
+                {
+                    continue;
+                }
+                let mut marker = Marker::default();
+                marker.position = segment_set
+                    .get_position(&G_DMSEGMENT_EVENTSTARTPOSITION_ITEM_KEY)
+                    .ok_or("Could not read marker position.".to_string())?;
+                marker.timecode = match (info.clip_edit_rate.as_ref(), event_edit_rate.as_ref()) {
+                    (Some(c_edit_rate), Some(e_edit_rate)) => {
+                        info.start_timecode
+                            + convert_length(
+                                c_edit_rate,
+                                e_edit_rate,
+                                marker.position - event_origin,
+                            )
+                    }
+                    _ => info.start_timecode + marker.position - event_origin,
+                };
+                if segment_set.has_item(&G_DMSEGMENT_EVENTCOMMENT_ITEM_KEY) {
+                    marker.comment = segment_set.get_string(&G_DMSEGMENT_EVENTCOMMENT_ITEM_KEY);
+                }
+                /* The marker color is an Avid RGBColor record of three UInt16 values */
+                if segment_set.has_item(&G_DMSEGMENT_COMMENTMARKERCOLOR_ITEM_KEY) {
+                    marker.color = match segment_set
+                        .get_item_value(&G_DMSEGMENT_COMMENTMARKERCOLOR_ITEM_KEY)
+                    {
+                        Some(ref value) if value.len() == 6 => Some(RGBColor {
+                            red: (value[0] as u16) << 8 | value[1] as u16,
+                            green: (value[2] as u16) << 8 | value[3] as u16,
+                            blue: (value[4] as u16) << 8 | value[5] as u16,
+                        }),
+                        _ => None,
+                    };
+                }
+                if segment_set.has_item(&G_DMSEGMENT_TRACKIDS_ITEM_KEY) {
+                    marker.track_ids = segment_set
+                        .get_uint32_array(&G_DMSEGMENT_TRACKIDS_ITEM_KEY)
+                        .ok_or("Could not read marker track ids.".to_string())?;
+                }
+                info.markers.push(marker);
+            }
+        }
+
         return Ok(info);
     }
 
+    /* Essence container with the picture coding, e.g. "DNxHD 1252 Frame-wrapped" */
+    pub fn describe_essence(&self) -> Option<String> {
+        let label = self.essence_container_label.as_ref()?;
+        Some(match self.picture_coding_label.as_ref() {
+            Some(coding) => label.describe_with_coding(coding),
+            None => label.describe(),
+        })
+    }
+
+    /* The essence container holds less than the material track claims if the capture was
+     * interrupted before the file was closed */
+    pub fn is_truncated(&self) -> bool {
+        match (
+            self.container_duration,
+            self.sample_rate.as_ref(),
+            self.track_duration,
+            self.clip_edit_rate.as_ref(),
+        ) {
+            (
+                Some(container_duration),
+                Some(sample_rate),
+                Some(track_duration),
+                Some(edit_rate),
+            ) => compare_length(edit_rate, track_duration, sample_rate, container_duration) > 0,
+            _ => false,
+        }
+    }
+
     pub fn is_renderfile(&self) -> bool {
         self.physical_package_name == Some("Precompute Source Mob".to_string())
     }
+
+    /* Classifies how the media was created. Renders are only reported for files referencing a
+     * precompute source mob, so that original media is never mistaken for a render. Effect
+     * renders and other precomputes are not told apart, the file does not record which. */
+    pub fn media_kind(&self) -> MediaKind {
+        if self.is_renderfile() {
+            return if self.is_video {
+                MediaKind::VideoRender
+            } else {
+                MediaKind::AudioRender
+            };
+        }
+
+        /* Consolidating and transcoding create new master clips named <clip>.new.NN */
+        if self
+            .clip_name
+            .as_ref()
+            .map_or(false, |name| is_new_master_clip_name(name))
+        {
+            /* Transcoded media derives from another file package, consolidated media still
+             * references the physical source */
+            let derived_from = self
+                .source_chain
+                .iter()
+                .skip_while(|hop| !hop.descriptor_type.is_file())
+                .nth(1);
+            return match derived_from.map(|hop| hop.descriptor_type) {
+                Some(PackageDescriptorType::Picture)
+                | Some(PackageDescriptorType::Sound)
+                | Some(PackageDescriptorType::Multiple) => MediaKind::Transcoded,
+                Some(PackageDescriptorType::Tape)
+                | Some(PackageDescriptorType::Import)
+                | Some(PackageDescriptorType::Recording) => MediaKind::Consolidated,
+                _ => MediaKind::Unknown,
+            };
+        }
+
+        match self.physical_package_type {
+            AvidPhysicalPackageType::ImportPhysType => MediaKind::Imported,
+            AvidPhysicalPackageType::TapePhysType | AvidPhysicalPackageType::RecordingPhysType => {
+                MediaKind::Captured
+            }
+            AvidPhysicalPackageType::UnkownPhysType => {
+                if self
+                    .mob_attributes
+                    .iter()
+                    .any(|&(ref name, _)| name.starts_with("_IMPORTSETTING"))
+                {
+                    MediaKind::Imported
+                } else {
+                    MediaKind::Unknown
+                }
+            }
+        }
+    }
+}
+
+/* Name of a master clip created by consolidating or transcoding, e.g. "clip.new.01" */
+fn is_new_master_clip_name(name: &str) -> bool {
+    match name.rfind(".new.") {
+        Some(index) => {
+            let suffix = &name[index + 5..];
+            !suffix.is_empty() && suffix.bytes().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
 }
@@ -1,12 +1,15 @@
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
 use num_rational::Rational32;
 use num_traits::Zero;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::fmt;
 use std::path::Path;
 use std::ptr;
+use std::sync::Arc;
 
-use ffi::consts::*;
+use crate::ffi::consts::*;
+use crate::ffi::index::{IndexTable, IndexTableSegment};
+use crate::ffi::labels;
 
 /* C Types */
 pub type uint8 = libc::uint8_t;
@@ -19,6 +22,7 @@ pub type int32 = libc::int32_t;
 pub type size_t = libc::size_t;
 pub type c_char = libc::c_char;
 pub type c_int = libc::c_int;
+pub type c_uint = libc::c_uint;
 pub type c_void = libc::c_void;
 
 fn convert_string(utf16str: *mut uint16) -> Option<String> {
@@ -37,167 +41,1369 @@ fn convert_string(utf16str: *mut uint16) -> Option<String> {
     }
 }
 
+pub fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> &'a str {
+    if s.len() >= prefix.len()
+        && s.is_char_boundary(prefix.len())
+        && s[..prefix.len()].eq_ignore_ascii_case(prefix)
+    {
+        &s[prefix.len()..]
+    } else {
+        s
+    }
+}
+
+/* Parses hex digits into octets, skipping the '.' and '-' group separators */
+fn parse_hex_octets(s: &str, octets: &mut [u8]) -> Result<(), String> {
+    let digits: Vec<u8> = s
+        .chars()
+        .filter(|&c| c != '.' && c != '-')
+        .map(|c| {
+            c.to_digit(16)
+                .map(|d| d as u8)
+                .ok_or(format!("'{}' is not a hex digit", c))
+        })
+        .collect::<Result<_, _>>()?;
+    if digits.len() != 2 * octets.len() {
+        return Err(format!(
+            "expected {} hex digits, found {}",
+            2 * octets.len(),
+            digits.len()
+        ));
+    }
+    for (octet, pair) in octets.iter_mut().zip(digits.chunks(2)) {
+        *octet = pair[0] << 4 | pair[1];
+    }
+    Ok(())
+}
+
 enum _MXFFile {}
+enum MXFMemoryFile {}
 pub enum MXFDataModel {}
 
-impl MXFDataModel {
-    pub fn is_subclass_of(&mut self, key_a: &MXFKey, key_b: &MXFKey) -> bool {
-        unsafe { !mxf_is_subclass_of(self, key_a, key_b).is_zero() }
+impl MXFDataModel {
+    pub fn is_subclass_of(&self, key_a: &MXFKey, key_b: &MXFKey) -> bool {
+        unsafe { !mxf_is_subclass_of(self, key_a, key_b).is_zero() }
+    }
+
+    /* Name of a registered set definition */
+    pub fn set_def_name(&self, mxfkey: &MXFKey) -> Option<String> {
+        unsafe {
+            let mut set_def = ptr::null_mut();
+            if mxf_find_set_def(self, mxfkey, &mut set_def).is_zero() {
+                return None;
+            }
+            c_string((*set_def).name)
+        }
+    }
+
+    /* Registers a set definition. The parent must be registered before the data model is
+     * finalised. */
+    pub fn register_set_def(
+        &mut self,
+        name: &str,
+        parent_key: &MXFKey,
+        mxfkey: &MXFKey,
+    ) -> Result<(), String> {
+        let c_name = CString::new(name).map_err(|_| "Name not CString compliant.".to_string())?;
+        unsafe {
+            if mxf_register_set_def(self, c_name.as_ptr(), parent_key, mxfkey).is_zero() {
+                return Err(format!("Could not register set definition {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    /* Registers an item definition with an unknown type, so its value is only available as
+     * bytes. A zero tag is used for dynamic tags, which are taken from the primer pack. */
+    pub fn register_item_def(
+        &mut self,
+        name: &str,
+        set_key: &MXFKey,
+        mxfkey: &MXFKey,
+        tag: u16,
+        is_required: bool,
+    ) -> Result<(), String> {
+        let c_name = CString::new(name).map_err(|_| "Name not CString compliant.".to_string())?;
+        unsafe {
+            if mxf_register_item_def(
+                self,
+                c_name.as_ptr(),
+                set_key,
+                mxfkey,
+                tag,
+                MXF_UNKNOWN_TYPE,
+                is_required as c_int,
+            )
+            .is_zero()
+            {
+                return Err(format!("Could not register item definition {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn has_set_def(&self, mxfkey: &MXFKey) -> bool {
+        let mut set_def = ptr::null_mut();
+        unsafe { !mxf_find_set_def(self, mxfkey, &mut set_def).is_zero() }
+    }
+
+    pub fn item_def(&self, mxfkey: &MXFKey) -> Option<ItemDef> {
+        unsafe {
+            let mut item_def = ptr::null_mut();
+            if mxf_find_item_def(self, mxfkey, &mut item_def).is_zero() {
+                return None;
+            }
+            let item_def: &MXFItemDef = item_def.as_ref().unwrap();
+            Some(ItemDef {
+                name: c_string(item_def.name),
+                set_key: item_def.set_def_key,
+                tag: item_def.local_tag,
+                item_type: self.item_type(item_def.type_id),
+            })
+        }
+    }
+
+    fn item_type(&self, type_id: c_uint) -> ItemType {
+        unsafe {
+            let item_type = match mxf_get_item_def_type(self, type_id).as_ref() {
+                Some(x) => x,
+                None => return ItemType::Unknown,
+            };
+            let name = c_string(item_type.name).unwrap_or_default();
+            /* The first two words of the info union are the basic size, the array element type
+             * and fixed size or the interpreted type and fixed size */
+            let words = &item_type.info as *const MXFCompoundTypeInfo as *const c_uint;
+            match item_type.category {
+                MXF_BASIC_TYPE_CAT => ItemType::Basic {
+                    name: name,
+                    size: *words,
+                },
+                MXF_ARRAY_TYPE_CAT => ItemType::Array {
+                    name: name,
+                    element: Box::new(self.item_type(*words)),
+                    fixed_size: *words.add(1),
+                },
+                MXF_COMPOUND_TYPE_CAT => ItemType::Compound {
+                    name: name,
+                    members: item_type
+                        .info
+                        .members
+                        .iter()
+                        .take_while(|member| !member.name.is_null())
+                        .map(|member| {
+                            (
+                                c_string(member.name).unwrap_or_default(),
+                                self.item_type(member.type_id),
+                            )
+                        })
+                        .collect(),
+                },
+                MXF_INTERPRET_TYPE_CAT => ItemType::Interpret {
+                    name: name,
+                    base: Box::new(self.item_type(*words)),
+                    fixed_size: *words.add(1),
+                },
+                _ => ItemType::Unknown,
+            }
+        }
+    }
+}
+
+fn c_string(value: *const c_char) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    unsafe { CStr::from_ptr(value).to_str().ok().map(String::from) }
+}
+
+/* Data model definitions, mirroring mxf/mxf_data_model.h of libMXF 1.0. The layout is not part
+ * of the libMXF API, so build.rs only accepts that version. */
+#[repr(C)]
+struct MXFSetDef {
+    name: *const c_char,
+    parent_set_def_key: MXFKey,
+    key: MXFKey,
+    item_defs: MXFList,
+    parent_set_def: *mut MXFSetDef,
+}
+
+#[repr(C)]
+struct MXFItemDef {
+    name: *const c_char,
+    set_def_key: MXFKey,
+    key: MXFKey,
+    local_tag: uint16,
+    type_id: c_uint,
+    is_required: c_int,
+}
+
+const MXF_UNKNOWN_TYPE: c_uint = 0;
+
+const MXF_BASIC_TYPE_CAT: c_int = 0;
+const MXF_ARRAY_TYPE_CAT: c_int = 1;
+const MXF_COMPOUND_TYPE_CAT: c_int = 2;
+const MXF_INTERPRET_TYPE_CAT: c_int = 3;
+
+#[repr(C)]
+struct MXFCompoundTypeMemberInfo {
+    name: *const c_char,
+    type_id: c_uint,
+}
+
+/* Largest member of the MXFItemType info union of libMXF 1.0, which allows compound types of up
+ * to 16 members */
+#[repr(C)]
+struct MXFCompoundTypeInfo {
+    members: [MXFCompoundTypeMemberInfo; 16],
+}
+
+#[repr(C)]
+struct MXFItemType {
+    category: c_int,
+    type_id: c_uint,
+    name: *const c_char,
+    info: MXFCompoundTypeInfo,
+}
+
+/* Item type of the data model down to its basic types. Types are told apart by name, which
+ * unlike the type ids is stable across libMXF versions. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemType {
+    Basic {
+        name: String,
+        size: u32,
+    },
+    Array {
+        name: String,
+        element: Box<ItemType>,
+        fixed_size: u32,
+    },
+    Compound {
+        name: String,
+        members: Vec<(String, ItemType)>,
+    },
+    Interpret {
+        name: String,
+        base: Box<ItemType>,
+        fixed_size: u32,
+    },
+    Unknown,
+}
+
+impl ItemType {
+    pub fn name(&self) -> &str {
+        match *self {
+            ItemType::Basic { ref name, .. }
+            | ItemType::Array { ref name, .. }
+            | ItemType::Compound { ref name, .. }
+            | ItemType::Interpret { ref name, .. } => name,
+            ItemType::Unknown => "Unknown",
+        }
+    }
+
+    /* Encoded size, None for variable length types */
+    pub fn size(&self) -> Option<usize> {
+        match *self {
+            ItemType::Basic { size, .. } => Some(size as usize),
+            ItemType::Array {
+                ref element,
+                fixed_size,
+                ..
+            } if fixed_size > 0 => element.size().map(|size| size * fixed_size as usize),
+            ItemType::Compound { ref members, .. } => {
+                members.iter().map(|&(_, ref member)| member.size()).sum()
+            }
+            ItemType::Interpret {
+                ref base,
+                fixed_size,
+                ..
+            } => match **base {
+                ItemType::Array { ref element, .. } if fixed_size > 0 => {
+                    element.size().map(|size| size * fixed_size as usize)
+                }
+                _ => base.size(),
+            },
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemDef {
+    pub name: Option<String>,
+    pub set_key: MXFKey,
+    pub tag: u16,
+    pub item_type: ItemType,
+}
+
+pub enum MXFPrimerPack {}
+
+/* A loaded and finalised data model including the Avid and archive preservation extensions. The
+ * model is only read once finalised, so it can be shared by files opened on different threads. */
+pub struct DataModel {
+    datamodel: *mut MXFDataModel,
+}
+
+unsafe impl Send for DataModel {}
+unsafe impl Sync for DataModel {}
+
+impl DataModel {
+    pub fn new() -> Result<DataModel, String> {
+        DataModel::with_registered(|_| Ok(()))
+    }
+
+    /* Registers additional set and item definitions before the data model is finalised */
+    pub fn with_registered<F>(register: F) -> Result<DataModel, String>
+    where
+        F: FnOnce(&mut MXFDataModel) -> Result<(), String>,
+    {
+        let mut datamodel = ptr::null_mut();
+        unsafe {
+            if mxf_load_data_model(&mut datamodel).is_zero() {
+                return Err("Could not load datamodel.".to_string());
+            }
+
+            if mxf_avid_load_extensions(datamodel).is_zero() {
+                mxf_free_data_model(&mut datamodel);
+                return Err("Could not load avid extensions.".to_string());
+            }
+
+            if mxf_app_load_extensions(datamodel).is_zero() {
+                mxf_free_data_model(&mut datamodel);
+                return Err("Could not load archive preservation extensions.".to_string());
+            }
+
+            if let Err(e) = register(datamodel.as_mut().unwrap()) {
+                mxf_free_data_model(&mut datamodel);
+                return Err(e);
+            }
+
+            if mxf_finalise_data_model(datamodel).is_zero() {
+                mxf_free_data_model(&mut datamodel);
+                return Err("Could not finalize datamodel.".to_string());
+            }
+        }
+
+        Ok(DataModel {
+            datamodel: datamodel,
+        })
+    }
+}
+
+impl Drop for DataModel {
+    fn drop(&mut self) {
+        unsafe {
+            mxf_free_data_model(&mut self.datamodel);
+        }
+    }
+}
+
+/* Where the header metadata is read from */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataSource {
+    /* The metadata in the header partition, even if it is open or incomplete */
+    Header,
+    /* The metadata in the footer or the last body partition carrying it, if the header
+     * partition is open or incomplete */
+    Complete,
+}
+
+impl Default for MetadataSource {
+    fn default() -> MetadataSource {
+        MetadataSource::Header
+    }
+}
+
+/* Index table segment key, see SMPTE 377 */
+const INDEX_TABLE_SEGMENT_KEY: MXFKey = MXFKey {
+    octet0: 0x06,
+    octet1: 0x0e,
+    octet2: 0x2b,
+    octet3: 0x34,
+    octet4: 0x02,
+    octet5: 0x53,
+    octet6: 0x01,
+    octet7: 0x01,
+    octet8: 0x0d,
+    octet9: 0x01,
+    octet10: 0x02,
+    octet11: 0x01,
+    octet12: 0x01,
+    octet13: 0x10,
+    octet14: 0x01,
+    octet15: 0x00,
+};
+
+/* Header metadata is encoded in memory before it is written */
+const HEADER_METADATA_CHUNK_SIZE: uint32 = 64 * 1024;
+
+/* Header partition pack key without the partition status octets */
+const HEADER_PARTITION_KEY_PREFIX: [u8; 14] = [
+    0x06, 0x0e, 0x2b, 0x34, 0x02, 0x05, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x01, 0x02,
+];
+
+/* Essence element key prefix of legacy Avid files, which predate the generic container keys.
+ * The registry version octet (7) is ignored. */
+const AVID_ESSENCE_ELEMENT_PREFIX: [u8; 12] = [
+    0x06, 0x0e, 0x2b, 0x34, 0x01, 0x02, 0x01, 0x01, 0x0e, 0x04, 0x03, 0x01,
+];
+
+/* Stub types */
+pub struct MXFFile {
+    mxffile: *mut _MXFFile,
+    headerpartition: *mut MXFPartition,
+    /* Partition the header metadata was read from, null if read from the header partition */
+    metadatapartition: *mut MXFPartition,
+    model: Arc<DataModel>,
+    headerdata: *mut MXFHeaderMetadata,
+    /* Backing bytes of a file read from memory, empty for files on disk */
+    _data: Vec<u8>,
+}
+
+/* A file exclusively owns its libMXF file handle, partitions and header metadata, and libMXF keeps
+ * no global state while reading, so it can be moved to another thread. It is not Sync: reading
+ * moves the file position and the header metadata is handed out mutably. */
+unsafe impl Send for MXFFile {}
+
+/* Reads the partition offsets from the random index pack at the end of the file */
+unsafe fn read_rip_offsets(mxffile: *mut _MXFFile) -> Vec<uint64> {
+    let mut rip = MXFRIP::default();
+    mxf_initialise_rip(&mut rip);
+    let mut offsets = Vec::new();
+    if !mxf_read_rip(mxffile, &mut rip).is_zero() {
+        offsets = rip
+            .entries
+            .get_iter()
+            .map(|entry| (*(entry as *mut MXFRIPEntry)).this_partition)
+            .collect();
+    }
+    mxf_clear_rip(&mut rip);
+
+    offsets
+}
+
+/* Finds the footer partition offset in the header partition pack or the random index pack */
+unsafe fn find_footer_partition(
+    mxffile: *mut _MXFFile,
+    headerpartition: &MXFPartition,
+) -> Option<uint64> {
+    if headerpartition.footer_partition != 0 {
+        return Some(headerpartition.footer_partition);
+    }
+
+    read_rip_offsets(mxffile)
+        .last()
+        .cloned()
+        .filter(|&offset| offset != 0)
+}
+
+/* Reads the partition pack at the offset, leaving the file positioned after it */
+unsafe fn read_partition_at(mxffile: *mut _MXFFile, offset: uint64) -> *mut MXFPartition {
+    let mut mxful = MXFKey::default();
+    let mut llen = 0;
+    let mut len = 0;
+    let mut partition = ptr::null_mut();
+    if mxf_file_seek(mxffile, offset as int64, libc::SEEK_SET).is_zero()
+        || mxf_read_kl(mxffile, &mut mxful, &mut llen, &mut len).is_zero()
+        || mxf_is_partition_pack(&mxful).is_zero()
+        || mxf_read_partition(mxffile, &mxful, &mut partition).is_zero()
+    {
+        return ptr::null_mut();
+    }
+
+    partition
+}
+
+/* Walks the partition chain back from the footer to the last partition carrying header metadata.
+ * On success the file is positioned after the partition pack. */
+unsafe fn read_metadata_partition(
+    mxffile: *mut _MXFFile,
+    headerpartition: &MXFPartition,
+) -> *mut MXFPartition {
+    let mut offset = match find_footer_partition(mxffile, headerpartition) {
+        Some(x) => x,
+        None => return ptr::null_mut(),
+    };
+
+    /* The header partition is at offset 0 */
+    while offset != 0 {
+        let mut partition = read_partition_at(mxffile, offset);
+        if partition.is_null() {
+            break;
+        }
+        let (header_byte_count, previous_partition) = {
+            let partition = partition.as_ref().unwrap();
+            (partition.header_byte_count, partition.previous_partition)
+        };
+        if header_byte_count > 0 {
+            return partition;
+        }
+        mxf_free_partition(&mut partition);
+
+        /* Guard against partition chains that do not lead back to the header */
+        if previous_partition >= offset {
+            break;
+        }
+        offset = previous_partition;
+    }
+
+    ptr::null_mut()
+}
+
+/* Reads the header metadata following a partition pack */
+unsafe fn read_header_metadata(
+    mxffile: *mut _MXFFile,
+    header_byte_count: uint64,
+    skip_dictionary: bool,
+    datamodel: *mut MXFDataModel,
+) -> Result<*mut MXFHeaderMetadata, String> {
+    let mut headerdata = ptr::null_mut();
+    let mut mxful = MXFKey::default();
+    let mut llen = 0;
+    let mut len = 0;
+
+    if mxf_read_next_nonfiller_kl(mxffile, &mut mxful, &mut llen, &mut len).is_zero() {
+        return Err("Could not read next nonfiller kl.".to_string());
+    }
+
+    if mxf_is_header_metadata(&mut mxful).is_zero() {
+        return Err("Is not header metadata.".to_string());
+    }
+
+    if mxf_create_header_metadata(&mut headerdata, datamodel).is_zero() {
+        return Err("Could not read header metadata.".to_string());
+    }
+
+    if mxf_avid_read_filtered_header_metadata(
+        mxffile,
+        skip_dictionary as c_int,
+        headerdata,
+        header_byte_count,
+        &mxful,
+        llen,
+        len,
+    )
+    .is_zero()
+    {
+        mxf_free_header_metadata(&mut headerdata);
+        return Err("Could not read header metadata.".to_string());
+    }
+
+    Ok(headerdata)
+}
+
+/* Converts to a null terminated UTF-16 string */
+fn to_utf16(value: &str) -> Vec<uint16> {
+    value.encode_utf16().chain(Some(0)).collect()
+}
+
+impl MXFFile {
+    /* Bytes needed to find the size of the header partition pack and header metadata: the key, the
+     * longest BER length and the partition pack items up to the header byte count */
+    pub const HEADER_PREFIX_SIZE: usize = 16 + 9 + 40;
+
+    /* Number of bytes from the start of the file to the end of the header metadata */
+    pub fn header_metadata_size(prefix: &[u8]) -> Result<u64, String> {
+        if prefix.len() < MXFFile::HEADER_PREFIX_SIZE || prefix[..14] != HEADER_PARTITION_KEY_PREFIX
+        {
+            return Err("Could not read header.".to_string());
+        }
+
+        /* BER length, see SMPTE 336 */
+        let (value_start, len) = if prefix[16] < 0x80 {
+            (17, u64::from(prefix[16]))
+        } else {
+            let llen = (prefix[16] & 0x7f) as usize;
+            if llen == 0 || llen > 8 {
+                return Err("Could not read header.".to_string());
+            }
+            let len = prefix[17..17 + llen]
+                .iter()
+                .fold(0, |value, &byte| value << 8 | u64::from(byte));
+            (17 + llen, len)
+        };
+        if len < 40 {
+            return Err("Could not read header partition.".to_string());
+        }
+        let header_byte_count = prefix[value_start + 32..value_start + 40]
+            .iter()
+            .fold(0, |value, &byte| value << 8 | u64::from(byte));
+
+        Ok(value_start as u64 + len + header_byte_count)
+    }
+
+    /* Archive preservation files are OP-1a, so the OP-Atom check can be skipped */
+    pub fn open(filename: &Path, op_atom_only: bool) -> Result<MXFFile, String> {
+        MXFFile::open_with_metadata(filename, op_atom_only, MetadataSource::Header)
+    }
+
+    pub fn open_with_metadata(
+        filename: &Path,
+        op_atom_only: bool,
+        source: MetadataSource,
+    ) -> Result<MXFFile, String> {
+        let model = Arc::new(DataModel::new()?);
+        MXFFile::open_with_model(filename, op_atom_only, source, &model)
+    }
+
+    /* Loading the data model dominates the time to open small files, so batch jobs build it once
+     * and share it */
+    pub fn open_with_model(
+        filename: &Path,
+        op_atom_only: bool,
+        source: MetadataSource,
+        model: &Arc<DataModel>,
+    ) -> Result<MXFFile, String> {
+        MXFFile::open_filtered(filename, op_atom_only, source, false, model.clone())
+    }
+
+    /* Reads the header metadata without the dictionary and meta-dictionary sets. Data
+     * definitions referenced by tracks and codec definitions cannot be resolved. */
+    pub fn probe(filename: &Path) -> Result<MXFFile, String> {
+        let model = Arc::new(DataModel::new()?);
+        MXFFile::probe_with_model(filename, &model)
+    }
+
+    pub fn probe_with_model(filename: &Path, model: &Arc<DataModel>) -> Result<MXFFile, String> {
+        MXFFile::open_filtered(filename, false, MetadataSource::Header, true, model.clone())
+    }
+
+    /* Opens a file for updating its header metadata in place, see write_bytes */
+    pub fn open_modify(filename: &Path, model: &Arc<DataModel>) -> Result<MXFFile, String> {
+        let filename = filename
+            .to_str()
+            .ok_or("Filename not UTF-8 compliant.".to_string())?;
+        let filename =
+            CString::new(filename).map_err(|_| "Filename not CString compliant.".to_string())?;
+        let mut mxffile = ptr::null_mut();
+
+        unsafe {
+            if mxf_disk_file_open_modify(filename.as_ptr(), &mut mxffile).is_zero() {
+                return Err("Could not open file for writing.".to_string());
+            }
+        }
+
+        MXFFile::read(
+            mxffile,
+            Vec::new(),
+            false,
+            MetadataSource::Header,
+            false,
+            model.clone(),
+        )
+    }
+
+    fn open_filtered(
+        filename: &Path,
+        op_atom_only: bool,
+        source: MetadataSource,
+        skip_dictionary: bool,
+        model: Arc<DataModel>,
+    ) -> Result<MXFFile, String> {
+        let filename = filename
+            .to_str()
+            .ok_or("Filename not UTF-8 compliant.".to_string())?;
+        let filename =
+            CString::new(filename).map_err(|_| "Filename not CString compliant.".to_string())?;
+        let mut mxffile = ptr::null_mut();
+
+        unsafe {
+            if mxf_disk_file_open_read(filename.as_ptr(), &mut mxffile).is_zero() {
+                return Err("Could not read file.".to_string());
+            }
+        }
+
+        MXFFile::read(
+            mxffile,
+            Vec::new(),
+            op_atom_only,
+            source,
+            skip_dictionary,
+            model,
+        )
+    }
+
+    /* Reads the header metadata from the start of a file held in memory, such as the bytes up to
+     * header_metadata_size. Partitions past the end of the data cannot be read. */
+    pub fn from_memory(
+        data: Vec<u8>,
+        op_atom_only: bool,
+        model: &Arc<DataModel>,
+    ) -> Result<MXFFile, String> {
+        let mut memfile = ptr::null_mut();
+        let mxffile;
+
+        unsafe {
+            if mxf_mem_file_open_read(data.as_ptr(), data.len() as int64, 0, &mut memfile).is_zero()
+            {
+                return Err("Could not read file.".to_string());
+            }
+            mxffile = mxf_mem_file_get_file(memfile);
+        }
+
+        MXFFile::read(
+            mxffile,
+            data,
+            op_atom_only,
+            MetadataSource::Header,
+            false,
+            model.clone(),
+        )
+    }
+
+    /* Reads the header metadata of an opened file, closing it on error */
+    fn read(
+        mut mxffile: *mut _MXFFile,
+        data: Vec<u8>,
+        op_atom_only: bool,
+        source: MetadataSource,
+        skip_dictionary: bool,
+        model: Arc<DataModel>,
+    ) -> Result<MXFFile, String> {
+        let mut headerpartition = ptr::null_mut();
+        let mut metadatapartition = ptr::null_mut();
+        let headerdata;
+        let mut mxful = MXFKey::default();
+        let mut llen = 0;
+        let mut len = 0;
+
+        unsafe {
+            if mxf_read_header_pp_kl(mxffile, &mut mxful, &mut llen, &mut len).is_zero() {
+                mxf_file_close(&mut mxffile);
+                return Err("Could not read header.".to_string());
+            }
+
+            if mxf_read_partition(mxffile, &mxful, &mut headerpartition).is_zero() {
+                mxf_file_close(&mut mxffile);
+                return Err("Could not read header partition.".to_string());
+            }
+
+            if op_atom_only
+                && !headerpartition
+                    .as_ref()
+                    .unwrap()
+                    .operational_pattern
+                    .is_op_atom()
+            {
+                mxf_file_close(&mut mxffile);
+                mxf_free_partition(&mut headerpartition);
+                return Err("Is not OP-Atom.".to_string());
+            }
+
+            /* An open or incomplete header has its final metadata in a later partition */
+            if source == MetadataSource::Complete
+                && !headerpartition.as_ref().unwrap().is_closed_complete()
+            {
+                let header_end = mxf_file_tell(mxffile);
+                metadatapartition =
+                    read_metadata_partition(mxffile, headerpartition.as_ref().unwrap());
+                if metadatapartition.is_null()
+                    && mxf_file_seek(mxffile, header_end, libc::SEEK_SET).is_zero()
+                {
+                    mxf_file_close(&mut mxffile);
+                    mxf_free_partition(&mut headerpartition);
+                    return Err("Could not seek to header metadata.".to_string());
+                }
+            }
+            let header_byte_count = if metadatapartition.is_null() {
+                headerpartition.as_ref().unwrap().header_byte_count
+            } else {
+                metadatapartition.as_ref().unwrap().header_byte_count
+            };
+
+            headerdata = match read_header_metadata(
+                mxffile,
+                header_byte_count,
+                skip_dictionary,
+                model.datamodel,
+            ) {
+                Ok(x) => x,
+                Err(e) => {
+                    mxf_file_close(&mut mxffile);
+                    mxf_free_partition(&mut headerpartition);
+                    mxf_free_partition(&mut metadatapartition);
+                    return Err(e);
+                }
+            };
+        }
+
+        Ok(MXFFile {
+            mxffile: mxffile,
+            headerpartition: headerpartition,
+            metadatapartition: metadatapartition,
+            model: model,
+            headerdata: headerdata,
+            _data: data,
+        })
+    }
+
+    pub fn get_mob_attribute(
+        name: &Vec<uint16>,
+        names: &MXFList,
+        values: &MXFList,
+    ) -> Option<String> {
+        unsafe {
+            let mut value = ptr::null_mut();
+            if mxf_avid_get_mob_attribute(name.as_ptr(), names, values, &mut value).is_zero() {
+                None
+            } else {
+                convert_string(value)
+            }
+        }
+    }
+
+    pub fn headerpartition(&self) -> &MXFPartition {
+        unsafe { self.headerpartition.as_ref().unwrap() }
+    }
+
+    /* The partition the header metadata was read from */
+    pub fn metadatapartition(&self) -> &MXFPartition {
+        unsafe {
+            match self.metadatapartition.as_ref() {
+                Some(x) => x,
+                None => self.headerpartition.as_ref().unwrap(),
+            }
+        }
+    }
+
+    /* Locates the footer partition, leaving the file position where it was */
+    pub fn footer_partition_offset(&self) -> Option<u64> {
+        unsafe {
+            let position = mxf_file_tell(self.mxffile);
+            let offset = find_footer_partition(self.mxffile, self.headerpartition());
+            if mxf_file_seek(self.mxffile, position, libc::SEEK_SET).is_zero() {
+                return None;
+            }
+            offset
+        }
+    }
+
+    /* Reads the index table segments of all partitions and locates the essence element their
+     * stream offsets refer to */
+    pub fn index_table(&self) -> Result<IndexTable, String> {
+        let mut table = IndexTable::default();
+        let mut found_essence = false;
+        let mut mxful = MXFKey::default();
+        let mut llen = 0;
+        let mut len = 0;
+
+        for partition in self.partitions()? {
+            unsafe {
+                /* Skip the partition pack */
+                if mxf_file_seek(
+                    self.mxffile,
+                    partition.this_partition as int64,
+                    libc::SEEK_SET,
+                )
+                .is_zero()
+                    || mxf_read_kl(self.mxffile, &mut mxful, &mut llen, &mut len).is_zero()
+                    || mxf_skip(self.mxffile, len).is_zero()
+                {
+                    return Err("Could not read partition pack.".to_string());
+                }
+
+                /* Header metadata and fill come before the index table segments, the essence
+                 * follows them */
+                while !mxf_read_kl(self.mxffile, &mut mxful, &mut llen, &mut len).is_zero() {
+                    if !mxf_is_partition_pack(&mxful).is_zero() {
+                        break;
+                    }
+                    if mxful.is_essence_element() {
+                        if !found_essence {
+                            table.essence_offset = mxf_file_tell(self.mxffile) as u64;
+                            table.essence_length = len;
+                            found_essence = true;
+                        }
+                        break;
+                    }
+                    if !mxf_is_index_table_segment(&mxful).is_zero() {
+                        /* The length is untrusted, the segments are bounded by the partition */
+                        if len > partition.index_byte_count || len > u64::from(u32::max_value()) {
+                            return Err("Invalid index table segment length.".to_string());
+                        }
+                        let mut data = vec![0; len as usize];
+                        if u64::from(mxf_file_read(
+                            self.mxffile,
+                            data.as_mut_ptr(),
+                            len as uint32,
+                        )) != len
+                        {
+                            return Err("Could not read index table segment.".to_string());
+                        }
+                        let segment = IndexTableSegment::parse(&data)?;
+                        /* Segments can be repeated in later partitions */
+                        if !table.segments.iter().any(|existing| {
+                            existing.index_sid == segment.index_sid
+                                && existing.index_start_position == segment.index_start_position
+                        }) {
+                            table.segments.push(segment);
+                        }
+                        continue;
+                    }
+                    if mxf_skip(self.mxffile, len).is_zero() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if table.segments.is_empty() {
+            return Err("Could not find index table segments.".to_string());
+        }
+        if !found_essence {
+            return Err("Could not find essence element.".to_string());
+        }
+        table
+            .segments
+            .sort_by_key(|segment| segment.index_start_position);
+
+        Ok(table)
+    }
+
+    pub fn read_bytes(&self, offset: u64, size: u32) -> Result<Vec<u8>, String> {
+        let mut data = vec![0; size as usize];
+        unsafe {
+            if mxf_file_seek(self.mxffile, offset as int64, libc::SEEK_SET).is_zero()
+                || mxf_file_read(self.mxffile, data.as_mut_ptr(), size) != size
+            {
+                return Err(format!(
+                    "Could not read {} bytes at offset {}.",
+                    size, offset
+                ));
+            }
+        }
+
+        Ok(data)
+    }
+
+    pub fn write_bytes(&self, offset: u64, data: &[u8]) -> Result<(), String> {
+        unsafe {
+            if mxf_file_seek(self.mxffile, offset as int64, libc::SEEK_SET).is_zero()
+                || mxf_file_write(self.mxffile, data.as_ptr(), data.len() as uint32)
+                    != data.len() as uint32
+            {
+                return Err(format!(
+                    "Could not write {} bytes at offset {}.",
+                    data.len(),
+                    offset
+                ));
+            }
+        }
+
+        Ok(())
     }
-}
 
-pub enum MXFPrimerPack {}
+    /* Replaces the header metadata with the copy in the partition at the offset */
+    pub fn read_partition_metadata(&mut self, offset: u64) -> Result<(), String> {
+        unsafe {
+            let mut partition = read_partition_at(self.mxffile, offset);
+            if partition.is_null() {
+                return Err(format!("Could not read partition at offset {}.", offset));
+            }
+            let header_byte_count = partition.as_ref().unwrap().header_byte_count;
+            let headerdata = match read_header_metadata(
+                self.mxffile,
+                header_byte_count,
+                false,
+                self.model.datamodel,
+            ) {
+                Ok(x) => x,
+                Err(e) => {
+                    mxf_free_partition(&mut partition);
+                    return Err(e);
+                }
+            };
 
-/* Stub types */
-pub struct MXFFile {
-    mxffile: *mut _MXFFile,
-    pub headerpartition: *mut MXFPartition,
-    pub datamodel: *mut MXFDataModel,
-    pub headerdata: *mut MXFHeaderMetadata,
-}
+            mxf_free_header_metadata(&mut self.headerdata);
+            mxf_free_partition(&mut self.metadatapartition);
+            self.headerdata = headerdata;
+            if offset == 0 {
+                mxf_free_partition(&mut partition);
+            } else {
+                self.metadatapartition = partition;
+            }
+        }
 
-impl MXFFile {
-    pub fn from_file(filename: &Path) -> Result<MXFFile, String> {
-        let filename = filename
-            .to_str()
-            .ok_or("Filename not UTF-8 compliant.".to_string())?;
-        let filename =
-            CString::new(filename).map_err(|_| "Filename not CString compliant.".to_string())?;
-        let mut mxffile = ptr::null_mut();
-        let mut headerpartition = ptr::null_mut();
-        let mut headerdata = ptr::null_mut();
-        let mut datamodel = ptr::null_mut();
+        Ok(())
+    }
+
+    /* Returns the offset of the primer pack and the end of the header metadata space, which
+     * includes any fill following the sets */
+    fn header_metadata_space(&self) -> Result<(u64, u64), String> {
+        let partition = self.metadatapartition();
         let mut mxful = MXFKey::default();
         let mut llen = 0;
         let mut len = 0;
 
         unsafe {
-            if mxf_disk_file_open_read(filename.as_ptr(), &mut mxffile).is_zero() {
-                return Err("Could not read file.".to_string());
+            if mxf_file_seek(
+                self.mxffile,
+                partition.this_partition as int64,
+                libc::SEEK_SET,
+            )
+            .is_zero()
+                || mxf_read_kl(self.mxffile, &mut mxful, &mut llen, &mut len).is_zero()
+                || mxf_skip(self.mxffile, len).is_zero()
+            {
+                return Err("Could not read partition pack.".to_string());
             }
-
-            if mxf_read_header_pp_kl(mxffile, &mut mxful, &mut llen, &mut len).is_zero() {
-                mxf_file_close(&mut mxffile);
-                return Err("Could not read header.".to_string());
+            let pack_end = mxf_file_tell(self.mxffile) as u64;
+            if mxf_read_next_nonfiller_kl(self.mxffile, &mut mxful, &mut llen, &mut len).is_zero() {
+                return Err("Could not read next nonfiller kl.".to_string());
             }
+            let start = mxf_file_tell(self.mxffile) as u64 - 16 - u64::from(llen);
 
-            if mxf_read_partition(mxffile, &mxful, &mut headerpartition).is_zero() {
-                mxf_file_close(&mut mxffile);
-                return Err("Could not read header partition.".to_string());
-            }
+            Ok((start, pack_end + partition.header_byte_count))
+        }
+    }
 
-            if !headerpartition
-                .as_ref()
-                .unwrap()
-                .operational_pattern
-                .is_op_atom()
+    /* Raw header metadata from the primer pack up to the end of the header byte count */
+    pub fn header_metadata_bytes(&self) -> Result<Vec<u8>, String> {
+        let (start, end) = self.header_metadata_space()?;
+        if end < start || end - start > u64::from(u32::max_value()) {
+            return Err("Could not read header metadata.".to_string());
+        }
+
+        self.read_bytes(start, (end - start) as u32)
+    }
+
+    /* Serialises the header metadata followed by fill up to the end of the existing space.
+     * Returns the offset to write the bytes at, or an error if the metadata has outgrown the
+     * space. */
+    pub fn encode_header_metadata(&self) -> Result<(u64, Vec<u8>), String> {
+        let (start, end) = self.header_metadata_space()?;
+        let mut memfile = ptr::null_mut();
+        let mut data = Vec::new();
+
+        unsafe {
+            if mxf_mem_file_open_new(HEADER_METADATA_CHUNK_SIZE, start as int64, &mut memfile)
+                .is_zero()
             {
-                mxf_file_close(&mut mxffile);
-                mxf_free_partition(&mut headerpartition);
-                return Err("Is not OP-Atom.".to_string());
+                return Err("Could not create memory file.".to_string());
             }
+            let mut mxffile = mxf_mem_file_get_file(memfile);
+            /* Avid writes four byte BER lengths */
+            mxf_file_set_min_llen(mxffile, 4);
 
-            if mxf_load_data_model(&mut datamodel).is_zero() {
+            if mxf_write_header_metadata(mxffile, self.headerdata).is_zero() {
                 mxf_file_close(&mut mxffile);
-                mxf_free_partition(&mut headerpartition);
-                return Err("Could not load datamodel.".to_string());
+                return Err("Could not write header metadata.".to_string());
             }
-
-            if mxf_avid_load_extensions(datamodel).is_zero() {
+            if mxf_fill_to_position(mxffile, end).is_zero() {
                 mxf_file_close(&mut mxffile);
-                mxf_free_partition(&mut headerpartition);
-                mxf_free_data_model(&mut datamodel);
-                return Err("Could not load avid extensions.".to_string());
+                return Err(format!(
+                    "Header metadata does not fit in the {} bytes available.",
+                    end - start
+                ));
             }
 
-            if mxf_finalise_data_model(datamodel).is_zero() {
-                mxf_file_close(&mut mxffile);
-                mxf_free_partition(&mut headerpartition);
-                mxf_free_data_model(&mut datamodel);
-                return Err("Could not finalize datamodel.".to_string());
+            for i in 0..mxf_mem_file_get_num_chunks(memfile) {
+                let chunk = std::slice::from_raw_parts(
+                    mxf_mem_file_get_chunk_data(memfile, i),
+                    mxf_mem_file_get_chunk_size(memfile, i) as usize,
+                );
+                data.extend_from_slice(chunk);
             }
+            mxf_file_close(&mut mxffile);
+        }
 
-            if mxf_read_next_nonfiller_kl(mxffile, &mut mxful, &mut llen, &mut len).is_zero() {
-                mxf_file_close(&mut mxffile);
-                mxf_free_partition(&mut headerpartition);
-                mxf_free_data_model(&mut datamodel);
-                return Err("Could not read next nonfiller kl.".to_string());
+        if data.len() as u64 != end - start {
+            return Err("Could not write header metadata.".to_string());
+        }
+
+        Ok((start, data))
+    }
+
+    /* Returns the file offset and size of the frame. Use index_table() for repeated lookups. */
+    pub fn frame_offset(&self, position: i64) -> Result<(u64, u32), String> {
+        self.index_table()?.frame_offset(position)
+    }
+
+    /* Lists the partition packs from the random index pack, or by walking the partition chain
+     * back from the footer if the file has none */
+    pub fn partitions(&self) -> Result<Vec<PartitionInfo>, String> {
+        unsafe {
+            let mut offsets = read_rip_offsets(self.mxffile);
+            if offsets.is_empty() {
+                let mut offset = find_footer_partition(self.mxffile, self.headerpartition());
+                while let Some(this_partition) = offset {
+                    let mut partition = read_partition_at(self.mxffile, this_partition);
+                    let previous_partition = match partition.as_ref() {
+                        Some(x) => x.previous_partition,
+                        None => {
+                            return Err(format!(
+                                "Could not read partition at offset {}.",
+                                this_partition
+                            ))
+                        }
+                    };
+                    mxf_free_partition(&mut partition);
+                    offsets.push(this_partition);
+                    /* Guard against partition chains that do not lead back to the header */
+                    offset = if previous_partition < this_partition {
+                        Some(previous_partition)
+                    } else {
+                        None
+                    };
+                }
+                offsets.reverse();
+                if offsets.first() != Some(&0) {
+                    offsets.insert(0, 0);
+                }
             }
 
-            if mxf_is_header_metadata(&mut mxful).is_zero() {
-                mxf_file_close(&mut mxffile);
-                mxf_free_partition(&mut headerpartition);
-                mxf_free_data_model(&mut datamodel);
-                return Err("Is not header metadata.".to_string());
+            let mut partitions = Vec::with_capacity(offsets.len());
+            for offset in offsets {
+                let mut partition = read_partition_at(self.mxffile, offset);
+                match partition.as_ref() {
+                    Some(x) => partitions.push(PartitionInfo::from(x)),
+                    None => return Err(format!("Could not read partition at offset {}.", offset)),
+                }
+                mxf_free_partition(&mut partition);
+            }
+
+            Ok(partitions)
+        }
+    }
+
+    /* The data model may be shared with other files, so it is only handed out immutably */
+    pub fn datamodel(&self) -> &MXFDataModel {
+        unsafe { self.model.datamodel.as_ref().unwrap() }
+    }
+
+    /* Sets are looked up and read through mutable references, as libMXF takes non-const
+     * pointers, so this is kept within the crate */
+    pub(crate) fn headerdata(&self) -> &mut MXFHeaderMetadata {
+        unsafe { self.headerdata.as_mut().unwrap() }
+    }
+}
+
+impl Drop for MXFFile {
+    fn drop(&mut self) {
+        unsafe {
+            mxf_file_close(&mut self.mxffile);
+            mxf_free_partition(&mut self.headerpartition);
+            mxf_free_partition(&mut self.metadatapartition);
+            mxf_free_header_metadata(&mut self.headerdata);
+        }
+    }
+}
+
+/* Writes a new file front to back: partition packs followed by header metadata, index table
+ * segments or essence. Partition packs are updated and the random index pack written on finish. */
+pub struct MXFWriter {
+    mxffile: *mut _MXFFile,
+    partitions: *mut MXFList,
+    /* Last partition written, owned by the partitions list */
+    partition: *mut MXFPartition,
+    /* Keeps the data model alive for the header metadata */
+    model: Arc<DataModel>,
+    headerdata: *mut MXFHeaderMetadata,
+}
+
+impl MXFWriter {
+    pub fn create(filename: &Path, model: &Arc<DataModel>) -> Result<MXFWriter, String> {
+        let filename = filename
+            .to_str()
+            .ok_or("Filename not UTF-8 compliant.".to_string())?;
+        let filename =
+            CString::new(filename).map_err(|_| "Filename not CString compliant.".to_string())?;
+        let mut mxffile = ptr::null_mut();
+        let mut partitions = ptr::null_mut();
+        let mut headerdata = ptr::null_mut();
+
+        unsafe {
+            if mxf_disk_file_open_new(filename.as_ptr(), &mut mxffile).is_zero() {
+                return Err("Could not create file.".to_string());
             }
+            /* Avid writes four byte BER lengths */
+            mxf_file_set_min_llen(mxffile, 4);
 
-            if mxf_create_header_metadata(&mut headerdata, datamodel).is_zero() {
+            if mxf_create_file_partitions(&mut partitions).is_zero() {
                 mxf_file_close(&mut mxffile);
-                mxf_free_partition(&mut headerpartition);
-                mxf_free_data_model(&mut datamodel);
-                return Err("Could not read header metadata.".to_string());
+                return Err("Could not create partitions.".to_string());
             }
 
-            if mxf_avid_read_filtered_header_metadata(
-                mxffile,
-                0,
-                headerdata,
-                headerpartition.as_ref().unwrap().header_byte_count,
-                &mxful,
-                llen,
-                len,
-            ).is_zero()
-            {
+            if mxf_create_header_metadata(&mut headerdata, model.datamodel).is_zero() {
                 mxf_file_close(&mut mxffile);
-                mxf_free_partition(&mut headerpartition);
-                mxf_free_data_model(&mut datamodel);
-                mxf_free_header_metadata(&mut headerdata);
-                return Err("Could not read header metadata.".to_string());
+                mxf_free_file_partitions(&mut partitions);
+                return Err("Could not create header metadata.".to_string());
             }
         }
 
-        Ok(MXFFile {
+        Ok(MXFWriter {
             mxffile: mxffile,
-            headerpartition: headerpartition,
-            datamodel: datamodel,
+            partitions: partitions,
+            partition: ptr::null_mut(),
+            model: model.clone(),
             headerdata: headerdata,
         })
     }
 
-    pub fn get_mob_attribute(
-        name: &Vec<uint16>,
-        names: &MXFList,
-        values: &MXFList,
-    ) -> Option<String> {
+    /* Sets are looked up and read through mutable references, as libMXF takes non-const
+     * pointers, so this is kept within the crate */
+    pub(crate) fn headerdata(&self) -> &mut MXFHeaderMetadata {
+        unsafe { self.headerdata.as_mut().unwrap() }
+    }
+
+    /* Starts a closed and complete partition, see set_partition_status */
+    pub fn write_partition(
+        &mut self,
+        kind: PartitionKind,
+        body_sid: u32,
+        index_sid: u32,
+        operational_pattern: &MXFKey,
+        essence_containers: &[MXFKey],
+    ) -> Result<(), String> {
+        let octet13 = match kind {
+            PartitionKind::Header => 0x02,
+            PartitionKind::Body => 0x03,
+            PartitionKind::Footer => 0x04,
+            PartitionKind::Unknown => return Err("Unknown partition kind.".to_string()),
+        };
+        let key = MXFKey::new(
+            0x06, 0x0e, 0x2b, 0x34, 0x02, 0x05, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x01, octet13,
+            0x04, 0x00,
+        );
+
         unsafe {
-            let mut value = ptr::null_mut();
-            if mxf_avid_get_mob_attribute(name.as_ptr(), names, values, &mut value).is_zero() {
-                None
-            } else {
-                convert_string(value)
+            let mut partition = ptr::null_mut();
+            if mxf_append_new_partition(self.partitions, &mut partition).is_zero() {
+                return Err("Could not create partition.".to_string());
             }
+            {
+                let partition = partition.as_mut().unwrap();
+                partition.key = key;
+                partition.body_sid = body_sid;
+                partition.indes_sid = index_sid;
+                partition.operational_pattern = *operational_pattern;
+            }
+            for label in essence_containers {
+                if mxf_append_partition_esscont_label(partition, label).is_zero() {
+                    return Err("Could not add partition essence container.".to_string());
+                }
+            }
+            if mxf_write_partition(self.mxffile, partition).is_zero() {
+                return Err("Could not write partition.".to_string());
+            }
+            self.partition = partition;
         }
+
+        Ok(())
     }
 
-    pub fn headerpartition(&self) -> &mut MXFPartition {
-        unsafe { self.headerpartition.as_mut().unwrap() }
+    /* Marks the current partition open or incomplete, as written while capturing. Partition packs
+     * are rewritten with their status on finish. */
+    pub fn set_partition_status(
+        &mut self,
+        is_closed: bool,
+        is_complete: bool,
+    ) -> Result<(), String> {
+        let partition = unsafe { self.partition.as_mut() }
+            .ok_or("No partition has been written.".to_string())?;
+        partition.key.octet14 = match (is_closed, is_complete) {
+            (false, false) => 0x01,
+            (true, false) => 0x02,
+            (false, true) => 0x03,
+            (true, true) => 0x04,
+        };
+
+        Ok(())
     }
 
-    pub fn datamodel(&self) -> &mut MXFDataModel {
-        unsafe { self.datamodel.as_mut().unwrap() }
+    /* Replaces the header metadata with an empty one, to write different metadata in a later
+     * partition. Sets of the previous header metadata must no longer be used. */
+    pub fn reset_header_metadata(&mut self) -> Result<(), String> {
+        unsafe {
+            let mut headerdata = ptr::null_mut();
+            if mxf_create_header_metadata(&mut headerdata, self.model.datamodel).is_zero() {
+                return Err("Could not create header metadata.".to_string());
+            }
+            mxf_free_header_metadata(&mut self.headerdata);
+            self.headerdata = headerdata;
+        }
+
+        Ok(())
     }
 
-    pub fn headerdata(&self) -> &mut MXFHeaderMetadata {
-        unsafe { self.headerdata.as_mut().unwrap() }
+    /* Writes the header metadata into the current partition followed by fill reserved for
+     * updating it in place */
+    pub fn write_header_metadata(&mut self, reserve: u32) -> Result<(), String> {
+        unsafe {
+            if self.partition.is_null()
+                || mxf_mark_header_start(self.mxffile, self.partition).is_zero()
+                || mxf_write_header_metadata(self.mxffile, self.headerdata).is_zero()
+                || (reserve > 0 && mxf_allocate_space(self.mxffile, reserve).is_zero())
+                || mxf_mark_header_end(self.mxffile, self.partition).is_zero()
+            {
+                return Err("Could not write header metadata.".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_index_table_segment(&mut self, segment: &IndexTableSegment) -> Result<(), String> {
+        let value = segment.encode(&MXFKey::generate_uuid());
+        unsafe {
+            if self.partition.is_null()
+                || mxf_mark_index_start(self.mxffile, self.partition).is_zero()
+                || mxf_write_fixed_kl(
+                    self.mxffile,
+                    &INDEX_TABLE_SEGMENT_KEY,
+                    4,
+                    value.len() as uint64,
+                )
+                .is_zero()
+                || mxf_file_write(self.mxffile, value.as_ptr(), value.len() as uint32)
+                    != value.len() as uint32
+                || mxf_mark_index_end(self.mxffile, self.partition).is_zero()
+            {
+                return Err("Could not write index table segment.".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_essence_element(&mut self, mxfkey: &MXFKey, data: &[u8]) -> Result<(), String> {
+        unsafe {
+            if mxf_write_fixed_kl(self.mxffile, mxfkey, 8, data.len() as uint64).is_zero() {
+                return Err("Could not write essence element.".to_string());
+            }
+            for chunk in data.chunks(u32::max_value() as usize) {
+                if mxf_file_write(self.mxffile, chunk.as_ptr(), chunk.len() as uint32)
+                    != chunk.len() as uint32
+                {
+                    return Err("Could not write essence element.".to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /* Writes the random index pack and updates the partition packs */
+    pub fn finish(self) -> Result<(), String> {
+        unsafe {
+            if mxf_write_rip(self.mxffile, self.partitions).is_zero()
+                || mxf_update_partitions(self.mxffile, self.partitions).is_zero()
+            {
+                return Err("Could not update partitions.".to_string());
+            }
+        }
+
+        Ok(())
     }
 }
 
-impl Drop for MXFFile {
+impl Drop for MXFWriter {
     fn drop(&mut self) {
         unsafe {
             mxf_file_close(&mut self.mxffile);
-            mxf_free_partition(&mut self.headerpartition);
-            mxf_free_data_model(&mut self.datamodel);
+            mxf_free_file_partitions(&mut self.partitions);
             mxf_free_header_metadata(&mut self.headerdata);
         }
     }
@@ -338,7 +1544,7 @@ impl fmt::Debug for AvidEssenceType {
 }
 
 #[repr(C)]
-#[derive(Default)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct MXFUmid {
     pub octet0: libc::uint8_t,
     pub octet1: libc::uint8_t,
@@ -375,6 +1581,14 @@ pub struct MXFUmid {
 }
 
 impl MXFUmid {
+    pub fn generate() -> MXFUmid {
+        let mut umid = MXFUmid::default();
+        unsafe {
+            mxf_generate_umid(&mut umid);
+        }
+        umid
+    }
+
     pub fn new(
         o0: u8,
         o1: u8,
@@ -444,11 +1658,107 @@ impl MXFUmid {
             octet31: o31,
         }
     }
+
+    pub fn as_bytes(&self) -> [u8; 32] {
+        [
+            self.octet0,
+            self.octet1,
+            self.octet2,
+            self.octet3,
+            self.octet4,
+            self.octet5,
+            self.octet6,
+            self.octet7,
+            self.octet8,
+            self.octet9,
+            self.octet10,
+            self.octet11,
+            self.octet12,
+            self.octet13,
+            self.octet14,
+            self.octet15,
+            self.octet16,
+            self.octet17,
+            self.octet18,
+            self.octet19,
+            self.octet20,
+            self.octet21,
+            self.octet22,
+            self.octet23,
+            self.octet24,
+            self.octet25,
+            self.octet26,
+            self.octet27,
+            self.octet28,
+            self.octet29,
+            self.octet30,
+            self.octet31,
+        ]
+    }
+
+    /* SMPTE 330M: 12 octet universal label, 1 octet length, 3 octet instance number and 16
+     * octet material number */
+    pub fn universal_label(&self) -> [u8; 12] {
+        let mut label = [0; 12];
+        label.copy_from_slice(&self.as_bytes()[0..12]);
+        label
+    }
+
+    pub fn length(&self) -> u8 {
+        self.octet12
+    }
+
+    pub fn instance_number(&self) -> u32 {
+        (self.octet13 as u32) << 16 | (self.octet14 as u32) << 8 | self.octet15 as u32
+    }
+
+    pub fn material_number(&self) -> [u8; 16] {
+        let mut material_number = [0; 16];
+        material_number.copy_from_slice(&self.as_bytes()[16..32]);
+        material_number
+    }
+
+    /* Avid writes mob ids in AAF and ALE files as one contiguous hex string */
+    pub fn to_avid_string(&self) -> String {
+        self.as_bytes()
+            .iter()
+            .map(|octet| format!("{:02x}", octet))
+            .collect()
+    }
+}
+
+impl From<[u8; 32]> for MXFUmid {
+    fn from(o: [u8; 32]) -> Self {
+        MXFUmid::new(
+            o[0], o[1], o[2], o[3], o[4], o[5], o[6], o[7], o[8], o[9], o[10], o[11], o[12], o[13],
+            o[14], o[15], o[16], o[17], o[18], o[19], o[20], o[21], o[22], o[23], o[24], o[25],
+            o[26], o[27], o[28], o[29], o[30], o[31],
+        )
+    }
 }
 
-impl std::cmp::PartialEq for MXFUmid {
-    fn eq(&self, other: &MXFUmid) -> bool {
-        unsafe { !mxf_equals_umid(self, other).is_zero() }
+impl std::str::FromStr for MXFUmid {
+    type Err = String;
+
+    /* Accepts the urn:smpte:umid: form as well as the plain Avid hex string */
+    fn from_str(s: &str) -> Result<MXFUmid, String> {
+        let mut octets = [0; 32];
+        parse_hex_octets(strip_prefix_ignore_case(s, "urn:smpte:umid:"), &mut octets)
+            .map_err(|err| format!("Invalid UMID {}: {}", s, err))?;
+        Ok(MXFUmid::from(octets))
+    }
+}
+
+impl fmt::Display for MXFUmid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "urn:smpte:umid:")?;
+        for (i, octet) in self.as_bytes().iter().enumerate() {
+            if i > 0 && i % 4 == 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{:02x}", octet)?;
+        }
+        Ok(())
     }
 }
 
@@ -582,11 +1892,101 @@ pub struct MXFPartition {
 }
 
 impl MXFPartition {
-    pub fn essence_containers(&mut self) -> &mut MXFList {
-        &mut self.essence_containers
+    pub fn essence_containers(&self) -> &MXFList {
+        &self.essence_containers
+    }
+
+    /* The partition kind is in octet 13 and the status in octet 14 of the partition pack key */
+    pub fn kind(&self) -> PartitionKind {
+        match self.key.octet13 {
+            0x02 => PartitionKind::Header,
+            0x03 => PartitionKind::Body,
+            0x04 => PartitionKind::Footer,
+            _ => PartitionKind::Unknown,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.key.octet14 == 0x02 || self.key.octet14 == 0x04
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.key.octet14 == 0x03 || self.key.octet14 == 0x04
+    }
+
+    pub fn is_closed_complete(&self) -> bool {
+        self.is_closed() && self.is_complete()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionKind {
+    Header,
+    Body,
+    Footer,
+    Unknown,
+}
+
+impl fmt::Display for PartitionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PartitionKind::Header => write!(f, "Header"),
+            PartitionKind::Body => write!(f, "Body"),
+            PartitionKind::Footer => write!(f, "Footer"),
+            PartitionKind::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionInfo {
+    pub kind: PartitionKind,
+    pub closed: bool,
+    pub complete: bool,
+    pub this_partition: u64,
+    pub previous_partition: u64,
+    pub footer_partition: u64,
+    pub header_byte_count: u64,
+    pub index_byte_count: u64,
+    pub index_sid: u32,
+    pub body_offset: u64,
+    pub body_sid: u32,
+    pub kag_size: u32,
+}
+
+impl<'a> From<&'a MXFPartition> for PartitionInfo {
+    fn from(partition: &MXFPartition) -> PartitionInfo {
+        PartitionInfo {
+            kind: partition.kind(),
+            closed: partition.is_closed(),
+            complete: partition.is_complete(),
+            this_partition: partition.this_partition,
+            previous_partition: partition.previous_partition,
+            footer_partition: partition.footer_partition,
+            header_byte_count: partition.header_byte_count,
+            index_byte_count: partition.index_byte_count,
+            index_sid: partition.indes_sid,
+            body_offset: partition.body_offset,
+            body_sid: partition.body_sid,
+            kag_size: partition.kag_size,
+        }
     }
 }
 
+/* MXF Random Index Pack */
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct MXFRIPEntry {
+    pub body_sid: uint32,
+    pub this_partition: uint64,
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct MXFRIP {
+    pub entries: MXFList,
+}
+
 /* MXF Header Metadata */
 #[repr(C)]
 #[derive(Clone)]
@@ -597,8 +1997,19 @@ pub struct MXFHeaderMetadata {
 }
 
 impl MXFHeaderMetadata {
-    pub fn datamodel(&mut self) -> &mut MXFDataModel {
-        unsafe { self.datamodel.as_mut().unwrap() }
+    pub fn create_set(&mut self, mxfkey: &MXFKey) -> Result<&'static mut MXFMetadataSet, String> {
+        unsafe {
+            let mut set = ptr::null_mut();
+            if mxf_create_set(self, mxfkey, &mut set).is_zero() {
+                return Err(format!("Could not create set {:?}.", mxfkey));
+            }
+
+            Ok(set.as_mut().unwrap())
+        }
+    }
+
+    pub fn datamodel(&self) -> &MXFDataModel {
+        unsafe { self.datamodel.as_ref().unwrap() }
     }
 
     pub fn find_singular_set_by_key(
@@ -659,6 +2070,40 @@ impl MXFHeaderMetadata {
         }
     }
 
+    pub fn get_codec_def_name(&mut self, codec: &MXFKey) -> Option<String> {
+        /* The codec definitions are strong referenced by the dictionary of the preface */
+        let preface_set = match self.find_singular_set_by_key(&G_PREFACE_SET_KEY) {
+            Ok(x) => x,
+            Err(_) => return None,
+        };
+        let dictionary_set = match preface_set.get_strongref(&G_PREFACE_DICTIONARY_ITEM_KEY) {
+            Some(x) => x,
+            None => return None,
+        };
+        let array_iter = match dictionary_set
+            .initialize_array_iterator(&G_DICTIONARY_CODECDEFINITIONS_ITEM_KEY)
+        {
+            Some(x) => x,
+            None => return None,
+        };
+        for item in array_iter {
+            let codec_def_set = match self.get_strongref(item) {
+                Some(x) => x,
+                /* Dark set not registered in dictionary. */
+                None => continue,
+            };
+            if codec_def_set
+                .get_ul(&G_DEFINITIONOBJECT_IDENTIFICATION_ITEM_KEY)
+                .as_ref()
+                == Some(codec)
+            {
+                return codec_def_set.get_string(&G_DEFINITIONOBJECT_NAME_ITEM_KEY);
+            }
+        }
+
+        None
+    }
+
     pub fn get_referenced_package(
         &mut self,
         source_pkg_id: &MXFUmid,
@@ -719,6 +2164,13 @@ impl MXFMetadataSet {
         unsafe { self.header_metadata.as_mut().unwrap() }
     }
 
+    pub fn get_items(&self) -> Vec<&MXFMetadataItem> {
+        self.items
+            .get_iter()
+            .map(|item| unsafe { &*(item as *const MXFMetadataItem) })
+            .collect()
+    }
+
     pub fn has_item(&mut self, mxfkey: &MXFKey) -> bool {
         unsafe { !mxf_have_item(self, mxfkey).is_zero() }
     }
@@ -766,6 +2218,28 @@ impl MXFMetadataSet {
         }
     }
 
+    pub fn get_boolean(&mut self, mxfkey: &MXFKey) -> Option<bool> {
+        unsafe {
+            let mut val = 0;
+            if mxf_get_boolean_item(self, mxfkey, &mut val).is_zero() {
+                None
+            } else {
+                Some(!val.is_zero())
+            }
+        }
+    }
+
+    pub fn get_int16(&mut self, mxfkey: &MXFKey) -> Option<int16> {
+        unsafe {
+            let mut val = 0;
+            if mxf_get_int16_item(self, mxfkey, &mut val).is_zero() {
+                None
+            } else {
+                Some(val)
+            }
+        }
+    }
+
     pub fn get_uint16(&mut self, mxfkey: &MXFKey) -> Option<uint16> {
         unsafe {
             let mut val = 0;
@@ -799,6 +2273,17 @@ impl MXFMetadataSet {
         }
     }
 
+    pub fn get_int64(&mut self, mxfkey: &MXFKey) -> Option<int64> {
+        unsafe {
+            let mut val = 0;
+            if mxf_get_int64_item(self, mxfkey, &mut val).is_zero() {
+                None
+            } else {
+                Some(val)
+            }
+        }
+    }
+
     pub fn get_umid(&mut self, mxfkey: &MXFKey) -> Option<MXFUmid> {
         unsafe {
             let mut val = MXFUmid::default();
@@ -810,107 +2295,432 @@ impl MXFMetadataSet {
         }
     }
 
-    pub fn get_timestamp(&mut self, mxfkey: &MXFKey) -> Option<NaiveDateTime> {
+    pub fn get_timestamp(&mut self, mxfkey: &MXFKey) -> Option<NaiveDateTime> {
+        unsafe {
+            let mut val = MXFTimestamp::default();
+            if mxf_get_timestamp_item(self, mxfkey, &mut val).is_zero() {
+                None
+            } else {
+                /* Unknown dates are written as all zeros */
+                NaiveDate::from_ymd_opt(val.year as i32, val.month as u32, val.day as u32).and_then(
+                    |date| date.and_hms_opt(val.hour as u32, val.min as u32, val.sec as u32),
+                )
+            }
+        }
+    }
+
+    pub fn get_array_len(&mut self, mxfkey: &MXFKey) -> Option<uint32> {
+        unsafe {
+            let mut val = 0;
+            if mxf_get_array_item_count(self, mxfkey, &mut val).is_zero() {
+                None
+            } else {
+                Some(val)
+            }
+        }
+    }
+
+    pub fn get_array_element(
+        &mut self,
+        mxfkey: &MXFKey,
+        index: uint32,
+    ) -> Option<&'static mut uint8> {
+        unsafe {
+            let mut val = ptr::null_mut();
+            if mxf_get_array_item_element(self, mxfkey, index, &mut val).is_zero() {
+                None
+            } else {
+                Some(val.as_mut().unwrap())
+            }
+        }
+    }
+
+    pub fn get_ul(&mut self, mxfkey: &MXFKey) -> Option<MXFKey> {
+        unsafe {
+            let mut val = MXFKey::default();
+            if mxf_get_ul_item(self, mxfkey, &mut val).is_zero() {
+                None
+            } else {
+                Some(val)
+            }
+        }
+    }
+
+    pub fn get_length(&mut self, mxfkey: &MXFKey) -> Option<int64> {
+        unsafe {
+            let mut val = 0;
+            if mxf_get_length_item(self, mxfkey, &mut val).is_zero() {
+                None
+            } else {
+                Some(val)
+            }
+        }
+    }
+
+    pub fn get_position(&mut self, mxfkey: &MXFKey) -> Option<int64> {
+        unsafe {
+            let mut val = 0;
+            if mxf_get_position_item(self, mxfkey, &mut val).is_zero() {
+                None
+            } else {
+                Some(val)
+            }
+        }
+    }
+
+    pub fn get_item_value(&mut self, mxfkey: &MXFKey) -> Option<Vec<u8>> {
+        unsafe {
+            let mut item = ptr::null_mut();
+            if mxf_get_item(self, mxfkey, &mut item).is_zero() {
+                None
+            } else {
+                let item: &MXFMetadataItem = item.as_ref().unwrap();
+                if item.value.is_null() {
+                    return Some(Vec::new());
+                }
+                Some(std::slice::from_raw_parts(item.value, item.length as usize).to_vec())
+            }
+        }
+    }
+
+    pub fn get_uint32_array(&mut self, mxfkey: &MXFKey) -> Option<Vec<uint32>> {
+        let count = self.get_array_len(mxfkey)?;
+        let mut values = Vec::with_capacity(count as usize);
+        for i in 0..count {
+            let element = self.get_array_element(mxfkey, i)?;
+            let value = unsafe { std::slice::from_raw_parts(element as *const uint8, 4) };
+            values.push(
+                (value[0] as u32) << 24
+                    | (value[1] as u32) << 16
+                    | (value[2] as u32) << 8
+                    | value[3] as u32,
+            );
+        }
+        Some(values)
+    }
+
+    pub fn get_strongref(&mut self, mxfkey: &MXFKey) -> Option<&'static mut MXFMetadataSet> {
+        unsafe {
+            let mut set = ptr::null_mut();
+            if mxf_get_strongref_item(self, mxfkey, &mut set).is_zero() {
+                None
+            } else {
+                Some(set.as_mut().unwrap())
+            }
+        }
+    }
+
+    pub fn is_subclass_of(&mut self, mxfkey: &MXFKey) -> bool {
+        unsafe { !mxf_set_is_subclass_of(self, mxfkey).is_zero() }
+    }
+
+    pub fn read_string_mob_attributes(&mut self) -> Option<(&mut MXFList, &mut MXFList)> {
+        unsafe {
+            let mut names = ptr::null_mut();
+            let mut values = ptr::null_mut();
+            if mxf_avid_read_string_mob_attributes(self, &mut names, &mut values).is_zero() {
+                None
+            } else {
+                Some((names.as_mut().unwrap(), values.as_mut().unwrap()))
+            }
+        }
+    }
+
+    pub fn get_mob_attributes(&mut self) -> Option<Vec<(String, String)>> {
+        let (names, values) = self.read_string_mob_attributes()?;
+        let attributes = names
+            .get_iter()
+            .zip(values.get_iter())
+            .filter_map(|(name, value)| {
+                Some((
+                    convert_string(name as *mut uint16)?,
+                    convert_string(value as *mut uint16)?,
+                ))
+            })
+            .collect();
+        MXFList::free(names);
+        MXFList::free(values);
+
+        Some(attributes)
+    }
+
+    pub fn set_string(&mut self, mxfkey: &MXFKey, value: &str) -> Result<(), String> {
+        unsafe {
+            if mxf_set_utf16string_item(self, mxfkey, to_utf16(value).as_ptr()).is_zero() {
+                return Err(format!("Could not set string item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_umid(&mut self, mxfkey: &MXFKey, value: &MXFUmid) -> Result<(), String> {
+        unsafe {
+            if mxf_set_umid_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set umid item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_ul(&mut self, mxfkey: &MXFKey, value: &MXFKey) -> Result<(), String> {
+        unsafe {
+            if mxf_set_ul_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set ul item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_uuid(&mut self, mxfkey: &MXFKey, value: &MXFKey) -> Result<(), String> {
+        unsafe {
+            if mxf_set_uuid_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set uuid item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_rational(&mut self, mxfkey: &MXFKey, value: &Rational32) -> Result<(), String> {
+        let value = MXFRational {
+            numerator: *value.numer(),
+            denominator: *value.denom(),
+        };
+        unsafe {
+            if mxf_set_rational_item(self, mxfkey, &value).is_zero() {
+                return Err(format!("Could not set rational item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_position(&mut self, mxfkey: &MXFKey, value: int64) -> Result<(), String> {
+        unsafe {
+            if mxf_set_position_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set position item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_length(&mut self, mxfkey: &MXFKey, value: int64) -> Result<(), String> {
+        unsafe {
+            if mxf_set_length_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set length item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_uint8(&mut self, mxfkey: &MXFKey, value: uint8) -> Result<(), String> {
+        unsafe {
+            if mxf_set_uint8_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set uint8 item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_boolean(&mut self, mxfkey: &MXFKey, value: bool) -> Result<(), String> {
+        unsafe {
+            if mxf_set_boolean_item(self, mxfkey, value as uint8).is_zero() {
+                return Err(format!("Could not set boolean item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_uint16(&mut self, mxfkey: &MXFKey, value: uint16) -> Result<(), String> {
+        unsafe {
+            if mxf_set_uint16_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set uint16 item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_uint32(&mut self, mxfkey: &MXFKey, value: uint32) -> Result<(), String> {
+        unsafe {
+            if mxf_set_uint32_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set uint32 item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_int32(&mut self, mxfkey: &MXFKey, value: int32) -> Result<(), String> {
+        unsafe {
+            if mxf_set_int32_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set int32 item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_int16(&mut self, mxfkey: &MXFKey, value: int16) -> Result<(), String> {
+        unsafe {
+            if mxf_set_int16_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set int16 item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_int64(&mut self, mxfkey: &MXFKey, value: int64) -> Result<(), String> {
+        unsafe {
+            if mxf_set_int64_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set int64 item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    /* Sets the raw value of an item, e.g. a record type libMXF has no setter for */
+    pub fn set_item_value(&mut self, mxfkey: &MXFKey, value: &[u8]) -> Result<(), String> {
+        if value.len() > u16::max_value() as usize {
+            return Err(format!("Item {:?} is too large.", mxfkey));
+        }
+        unsafe {
+            if mxf_set_item(self, mxfkey, value.as_ptr(), value.len() as uint16).is_zero() {
+                return Err(format!("Could not set item {:?}.", mxfkey));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_timestamp(&mut self, mxfkey: &MXFKey, value: &NaiveDateTime) -> Result<(), String> {
+        let value = MXFTimestamp {
+            year: value.year() as int16,
+            month: value.month() as uint8,
+            day: value.day() as uint8,
+            hour: value.hour() as uint8,
+            min: value.minute() as uint8,
+            sec: value.second() as uint8,
+            qmsec: 0,
+        };
         unsafe {
-            let mut val = MXFTimestamp::default();
-            if mxf_get_timestamp_item(self, mxfkey, &mut val).is_zero() {
-                None
-            } else {
-                Some(
-                    NaiveDate::from_ymd(val.year as i32, val.month as u32, val.day as u32).and_hms(
-                        val.hour as u32,
-                        val.min as u32,
-                        val.sec as u32,
-                    ),
-                )
+            if mxf_set_timestamp_item(self, mxfkey, &value).is_zero() {
+                return Err(format!("Could not set timestamp item {:?}.", mxfkey));
             }
         }
+
+        Ok(())
     }
 
-    pub fn get_array_len(&mut self, mxfkey: &MXFKey) -> Option<uint32> {
+    pub fn set_ul_array(&mut self, mxfkey: &MXFKey, values: &[MXFKey]) -> Result<(), String> {
         unsafe {
-            let mut val = 0;
-            if mxf_get_array_item_count(self, mxfkey, &mut val).is_zero() {
-                None
-            } else {
-                Some(val)
+            let mut elements = ptr::null_mut();
+            if mxf_alloc_array_item_elements(
+                self,
+                mxfkey,
+                16,
+                values.len() as uint32,
+                &mut elements,
+            )
+            .is_zero()
+            {
+                return Err(format!("Could not set ul array item {:?}.", mxfkey));
+            }
+            for (i, value) in values.iter().enumerate() {
+                ptr::copy_nonoverlapping(value.as_bytes().as_ptr(), elements.add(i * 16), 16);
             }
         }
+
+        Ok(())
     }
 
-    pub fn get_array_element(
-        &mut self,
-        mxfkey: &MXFKey,
-        index: uint32,
-    ) -> Option<&'static mut uint8> {
+    pub fn set_uint32_array(&mut self, mxfkey: &MXFKey, values: &[u32]) -> Result<(), String> {
         unsafe {
-            let mut val = ptr::null_mut();
-            if mxf_get_array_item_element(self, mxfkey, index, &mut val).is_zero() {
-                None
-            } else {
-                Some(val.as_mut().unwrap())
+            let mut elements = ptr::null_mut();
+            if mxf_alloc_array_item_elements(self, mxfkey, 4, values.len() as uint32, &mut elements)
+                .is_zero()
+            {
+                return Err(format!("Could not set uint32 array item {:?}.", mxfkey));
+            }
+            for (i, value) in values.iter().enumerate() {
+                ptr::copy_nonoverlapping(value.to_be_bytes().as_ptr(), elements.add(i * 4), 4);
             }
         }
+
+        Ok(())
     }
 
-    pub fn get_ul(&mut self, mxfkey: &MXFKey) -> Option<MXFKey> {
+    pub fn set_strongref(&mut self, mxfkey: &MXFKey, value: &MXFMetadataSet) -> Result<(), String> {
         unsafe {
-            let mut val = MXFKey::default();
-            if mxf_get_ul_item(self, mxfkey, &mut val).is_zero() {
-                None
-            } else {
-                Some(val)
+            if mxf_set_strongref_item(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not set strong reference item {:?}.", mxfkey));
             }
         }
+
+        Ok(())
     }
 
-    pub fn get_length(&mut self, mxfkey: &MXFKey) -> Option<int64> {
+    pub fn add_strongref(&mut self, mxfkey: &MXFKey, value: &MXFMetadataSet) -> Result<(), String> {
         unsafe {
-            let mut val = 0;
-            if mxf_get_length_item(self, mxfkey, &mut val).is_zero() {
-                None
-            } else {
-                Some(val)
+            if mxf_add_array_item_strongref(self, mxfkey, value).is_zero() {
+                return Err(format!("Could not add strong reference item {:?}.", mxfkey));
             }
         }
+
+        Ok(())
     }
 
-    pub fn get_position(&mut self, mxfkey: &MXFKey) -> Option<int64> {
+    pub fn set_indirect_string(&mut self, mxfkey: &MXFKey, value: &str) -> Result<(), String> {
         unsafe {
-            let mut val = 0;
-            if mxf_get_position_item(self, mxfkey, &mut val).is_zero() {
-                None
-            } else {
-                Some(val)
+            if mxf_avid_set_indirect_string_item(self, mxfkey, to_utf16(value).as_ptr()).is_zero() {
+                return Err(format!("Could not set indirect string item {:?}.", mxfkey));
             }
         }
+
+        Ok(())
     }
 
-    pub fn get_strongref(&mut self, mxfkey: &MXFKey) -> Option<&'static mut MXFMetadataSet> {
+    pub fn attach_mob_attribute(&mut self, name: &str, value: &str) -> Result<(), String> {
         unsafe {
-            let mut set = ptr::null_mut();
-            if mxf_get_strongref_item(self, mxfkey, &mut set).is_zero() {
-                None
-            } else {
-                Some(set.as_mut().unwrap())
+            if mxf_avid_attach_mob_attribute(
+                self.header_metadata,
+                self,
+                to_utf16(name).as_ptr(),
+                to_utf16(value).as_ptr(),
+            )
+            .is_zero()
+            {
+                return Err(format!("Could not attach mob attribute {}.", name));
             }
         }
-    }
 
-    pub fn is_subclass_of(&mut self, mxfkey: &MXFKey) -> bool {
-        unsafe { !mxf_set_is_subclass_of(self, mxfkey).is_zero() }
+        Ok(())
     }
 
-    pub fn read_string_mob_attributes(&mut self) -> Option<(&mut MXFList, &mut MXFList)> {
+    pub fn attach_user_comment(&mut self, name: &str, value: &str) -> Result<(), String> {
         unsafe {
-            let mut names = ptr::null_mut();
-            let mut values = ptr::null_mut();
-            if mxf_avid_read_string_mob_attributes(self, &mut names, &mut values).is_zero() {
-                None
-            } else {
-                Some((names.as_mut().unwrap(), values.as_mut().unwrap()))
+            if mxf_avid_attach_user_comment(
+                self.header_metadata,
+                self,
+                to_utf16(name).as_ptr(),
+                to_utf16(value).as_ptr(),
+            )
+            .is_zero()
+            {
+                return Err(format!("Could not attach user comment {}.", name));
             }
         }
+
+        Ok(())
     }
 
     pub fn read_string_tagged_values(
@@ -1075,6 +2885,15 @@ pub struct MXFMetadataItem {
     pub set: *mut MXFMetadataSet,
 }
 
+impl MXFMetadataItem {
+    pub fn get_value(&self) -> &[u8] {
+        if self.value.is_null() {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.value, self.length as usize) }
+    }
+}
+
 /* MXF Array Item Iterator */
 #[derive(Debug)]
 #[repr(C)]
@@ -1170,7 +2989,7 @@ impl std::fmt::Debug for MXFTimestamp {
 
 /* MXF Key */
 #[repr(C)]
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct MXFKey {
     pub octet0: libc::uint8_t,
     pub octet1: libc::uint8_t,
@@ -1191,6 +3010,15 @@ pub struct MXFKey {
 }
 
 impl MXFKey {
+    /* Generates a random UUID, which shares the key layout */
+    pub fn generate_uuid() -> MXFKey {
+        let mut uuid = MXFKey::default();
+        unsafe {
+            mxf_generate_uuid(&mut uuid);
+        }
+        uuid
+    }
+
     pub fn new(
         o0: u8,
         o1: u8,
@@ -1268,32 +3096,131 @@ impl MXFKey {
             }
         }
     }
+
+    pub fn as_bytes(&self) -> [u8; 16] {
+        [
+            self.octet0,
+            self.octet1,
+            self.octet2,
+            self.octet3,
+            self.octet4,
+            self.octet5,
+            self.octet6,
+            self.octet7,
+            self.octet8,
+            self.octet9,
+            self.octet10,
+            self.octet11,
+            self.octet12,
+            self.octet13,
+            self.octet14,
+            self.octet15,
+        ]
+    }
+
+    /* Dotted hex form without the urn prefix, e.g. 060e2b34.04010101.0d010301.02110200 */
+    pub fn to_hex_string(&self) -> String {
+        let mut hex = String::with_capacity(35);
+        for (i, octet) in self.as_bytes().iter().enumerate() {
+            if i > 0 && i % 4 == 0 {
+                hex.push('.');
+            }
+            hex.push_str(&format!("{:02x}", octet));
+        }
+        hex
+    }
+
+    /* Generic container essence element keys and the Avid legacy keys */
+    pub fn is_essence_element(&self) -> bool {
+        let octets = self.as_bytes();
+        let is_gc_element = unsafe { !mxf_is_gc_essence_element(self).is_zero() };
+        is_gc_element
+            || (octets[..7] == AVID_ESSENCE_ELEMENT_PREFIX[..7]
+                && octets[8..12] == AVID_ESSENCE_ELEMENT_PREFIX[8..])
+    }
+
+    pub fn is_smpte_ul(&self) -> bool {
+        self.octet0 == 0x06 && self.octet1 == 0x0e && self.octet2 == 0x2b && self.octet3 == 0x34
+    }
+
+    /* SMPTE recommends ignoring the registry version (octet 7) when comparing labels. Keys
+     * that aren't SMPTE ULs (e.g. swapped AAF UUIDs) are compared in full. */
+    pub fn eq_ignore_version(&self, other: &MXFKey) -> bool {
+        self.cmp_ignore_version(other) == std::cmp::Ordering::Equal
+    }
+
+    pub fn cmp_ignore_version(&self, other: &MXFKey) -> std::cmp::Ordering {
+        let mut key = *self;
+        if self.is_smpte_ul() && other.is_smpte_ul() {
+            key.octet7 = other.octet7;
+        }
+        key.cmp(other)
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        labels::label_name(self)
+    }
+
+    pub fn describe(&self) -> String {
+        match self.name() {
+            Some(name) => name.to_string(),
+            None => format!(
+                "unknown {} {}",
+                labels::label_kind(self),
+                self.to_hex_string()
+            ),
+        }
+    }
+
+    /* Combines an essence container label with the coding label of its essence, e.g. "DNxHD
+     * 1252 Frame-wrapped", as the container labels only name the codec family */
+    pub fn describe_with_coding(&self, coding: &MXFKey) -> String {
+        let wrapping = self.name().and_then(|name| {
+            ["Frame-wrapped", "Clip-wrapped"]
+                .iter()
+                .find(|&&wrapping| name.ends_with(wrapping))
+        });
+        match (coding.name(), wrapping) {
+            (Some(coding_name), Some(wrapping)) => format!("{} {}", coding_name, wrapping),
+            _ => self.describe(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<MXFKey> {
+        labels::label_by_name(name)
+    }
 }
 
-impl From<*mut c_void> for MXFKey {
-    fn from(raw: *mut c_void) -> Self {
-        unsafe { (*(raw as *mut MXFKey)).clone() }
+impl From<[u8; 16]> for MXFKey {
+    fn from(o: [u8; 16]) -> Self {
+        MXFKey::new(
+            o[0], o[1], o[2], o[3], o[4], o[5], o[6], o[7], o[8], o[9], o[10], o[11], o[12], o[13],
+            o[14], o[15],
+        )
+    }
+}
+
+impl std::str::FromStr for MXFKey {
+    type Err = String;
+
+    /* Accepts the urn:smpte:ul: form as well as the plain dotted hex form */
+    fn from_str(s: &str) -> Result<MXFKey, String> {
+        let mut octets = [0; 16];
+        parse_hex_octets(strip_prefix_ignore_case(s, "urn:smpte:ul:"), &mut octets)
+            .map_err(|err| format!("Invalid UL {}: {}", s, err))?;
+        Ok(MXFKey::from(octets))
+    }
+}
+
+impl fmt::Display for MXFKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "urn:smpte:ul:{}", self.to_hex_string())
     }
 }
 
-impl std::cmp::PartialEq for MXFKey {
-    fn eq(&self, other: &MXFKey) -> bool {
-        self.octet0 == other.octet0
-            && self.octet1 == other.octet1
-            && self.octet2 == other.octet2
-            && self.octet3 == other.octet3
-            && self.octet4 == other.octet4
-            && self.octet5 == other.octet5
-            && self.octet6 == other.octet6
-            && self.octet7 == other.octet7
-            && self.octet8 == other.octet8
-            && self.octet9 == other.octet9
-            && self.octet10 == other.octet10
-            && self.octet11 == other.octet11
-            && self.octet12 == other.octet12
-            && self.octet13 == other.octet13
-            && self.octet14 == other.octet14
-            && self.octet15 == other.octet15
+impl From<*mut c_void> for MXFKey {
+    fn from(raw: *mut c_void) -> Self {
+        unsafe { (*(raw as *mut MXFKey)).clone() }
     }
 }
 
@@ -1325,16 +3252,54 @@ impl std::fmt::Debug for MXFKey {
 extern "C" {
     /* mxf_file.h */
     fn mxf_disk_file_open_read(filename: *const c_char, mxffile: *mut *mut _MXFFile) -> c_int;
+    fn mxf_disk_file_open_new(filename: *const c_char, mxffile: *mut *mut _MXFFile) -> c_int;
+    fn mxf_disk_file_open_modify(filename: *const c_char, mxffile: *mut *mut _MXFFile) -> c_int;
     fn mxf_file_close(mxffile: *mut *mut _MXFFile);
+    fn mxf_file_seek(mxffile: *mut _MXFFile, offset: int64, whence: c_int) -> c_int;
+    fn mxf_file_tell(mxffile: *mut _MXFFile) -> int64;
+    fn mxf_file_read(mxffile: *mut _MXFFile, data: *mut uint8, count: uint32) -> uint32;
+    fn mxf_file_write(mxffile: *mut _MXFFile, data: *const uint8, count: uint32) -> uint32;
+    fn mxf_file_set_min_llen(mxffile: *mut _MXFFile, llen: uint8);
+    fn mxf_write_fixed_kl(
+        mxffile: *mut _MXFFile,
+        mxfkey: *const MXFKey,
+        llen: uint8,
+        len: uint64,
+    ) -> c_int;
+    fn mxf_skip(mxffile: *mut _MXFFile, len: uint64) -> c_int;
+    fn mxf_read_kl(
+        mxffile: *mut _MXFFile,
+        mxfkey: *mut MXFKey,
+        llen: *mut uint8,
+        len: *mut uint64,
+    ) -> c_int;
+
+    /* mxf_memory_file.h */
+    fn mxf_mem_file_open_read(
+        data: *const uint8,
+        size: int64,
+        virtual_start_pos: int64,
+        memfile: *mut *mut MXFMemoryFile,
+    ) -> c_int;
+    fn mxf_mem_file_open_new(
+        chunk_size: uint32,
+        virtual_start_pos: int64,
+        memfile: *mut *mut MXFMemoryFile,
+    ) -> c_int;
+    fn mxf_mem_file_get_file(memfile: *mut MXFMemoryFile) -> *mut _MXFFile;
+    fn mxf_mem_file_get_num_chunks(memfile: *mut MXFMemoryFile) -> uint32;
+    fn mxf_mem_file_get_chunk_data(memfile: *mut MXFMemoryFile, index: uint32) -> *mut uint8;
+    fn mxf_mem_file_get_chunk_size(memfile: *mut MXFMemoryFile, index: uint32) -> int64;
 
     /* mxf_utils.h */
+    fn mxf_generate_umid(umid: *mut MXFUmid);
+    fn mxf_generate_uuid(uuid: *mut MXFKey);
     fn mxf_find_set_by_key(
         headerdata: *mut MXFHeaderMetadata,
         mxfkey: *const MXFKey,
         list: *mut *mut MXFList,
     ) -> c_int;
     fn mxf_utf16_to_utf8(u8_str: *mut u8, u16_str: *const uint16, u8_size: size_t) -> size_t;
-    fn mxf_equals_umid(umid_a: *const MXFUmid, umid_b: *const MXFUmid) -> c_int;
     fn mxf_get_strongref(
         hederdata: *mut MXFHeaderMetadata,
         value: *const uint8,
@@ -1370,8 +3335,48 @@ extern "C" {
         len: *mut uint64,
     ) -> c_int;
     fn mxf_free_partition(partition: *mut *mut MXFPartition);
+    fn mxf_is_partition_pack(mxfkey: *const MXFKey) -> c_int;
+    fn mxf_fill_to_position(mxffile: *mut _MXFFile, position: uint64) -> c_int;
+    fn mxf_allocate_space(mxffile: *mut _MXFFile, size: uint32) -> c_int;
+    fn mxf_create_file_partitions(partitions: *mut *mut MXFList) -> c_int;
+    fn mxf_free_file_partitions(partitions: *mut *mut MXFList);
+    fn mxf_append_new_partition(
+        partitions: *mut MXFList,
+        partition: *mut *mut MXFPartition,
+    ) -> c_int;
+    fn mxf_append_partition_esscont_label(
+        partition: *mut MXFPartition,
+        label: *const MXFKey,
+    ) -> c_int;
+    fn mxf_write_partition(mxffile: *mut _MXFFile, partition: *mut MXFPartition) -> c_int;
+    fn mxf_update_partitions(mxffile: *mut _MXFFile, partitions: *mut MXFList) -> c_int;
+    fn mxf_write_rip(mxffile: *mut _MXFFile, partitions: *mut MXFList) -> c_int;
+    fn mxf_mark_header_start(mxffile: *mut _MXFFile, partition: *mut MXFPartition) -> c_int;
+    fn mxf_mark_header_end(mxffile: *mut _MXFFile, partition: *mut MXFPartition) -> c_int;
+    fn mxf_mark_index_start(mxffile: *mut _MXFFile, partition: *mut MXFPartition) -> c_int;
+    fn mxf_mark_index_end(mxffile: *mut _MXFFile, partition: *mut MXFPartition) -> c_int;
+    fn mxf_initialise_rip(rip: *mut MXFRIP);
+    fn mxf_clear_rip(rip: *mut MXFRIP);
+    fn mxf_read_rip(mxffile: *mut _MXFFile, rip: *mut MXFRIP) -> c_int;
 
     /* mxf_avid.h */
+    fn mxf_avid_set_indirect_string_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *const uint16,
+    ) -> c_int;
+    fn mxf_avid_attach_mob_attribute(
+        headerdata: *mut MXFHeaderMetadata,
+        dataset: *mut MXFMetadataSet,
+        name: *const uint16,
+        value: *const uint16,
+    ) -> c_int;
+    fn mxf_avid_attach_user_comment(
+        headerdata: *mut MXFHeaderMetadata,
+        dataset: *mut MXFMetadataSet,
+        name: *const uint16,
+        value: *const uint16,
+    ) -> c_int;
     fn mxf_avid_read_filtered_header_metadata(
         mxffile: *mut _MXFFile,
         skip_data_refs: c_int,
@@ -1410,6 +3415,12 @@ extern "C" {
         datadef: *mut MXFKey,
     ) -> c_int;
 
+    /* mxf_index_table.h */
+    fn mxf_is_index_table_segment(mxfkey: *const MXFKey) -> c_int;
+
+    /* mxf_app.h */
+    fn mxf_app_load_extensions(datamodel: *mut MXFDataModel) -> c_int;
+
     /* mxf_header_metadata.h */
     fn mxf_is_header_metadata(mxfkey: *const MXFKey) -> c_int;
     fn mxf_create_header_metadata(
@@ -1417,6 +3428,113 @@ extern "C" {
         datamodel: *const MXFDataModel,
     ) -> c_int;
     fn mxf_free_header_metadata(headerdata: *mut *mut MXFHeaderMetadata);
+    fn mxf_write_header_metadata(
+        mxffile: *mut _MXFFile,
+        headerdata: *mut MXFHeaderMetadata,
+    ) -> c_int;
+    fn mxf_set_utf16string_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *const uint16,
+    ) -> c_int;
+    fn mxf_create_set(
+        headerdata: *mut MXFHeaderMetadata,
+        mxfkey: *const MXFKey,
+        dataset: *mut *mut MXFMetadataSet,
+    ) -> c_int;
+    fn mxf_set_umid_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *const MXFUmid,
+    ) -> c_int;
+    fn mxf_set_ul_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *const MXFKey,
+    ) -> c_int;
+    fn mxf_set_uuid_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *const MXFKey,
+    ) -> c_int;
+    fn mxf_set_rational_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *const MXFRational,
+    ) -> c_int;
+    fn mxf_set_position_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: int64,
+    ) -> c_int;
+    fn mxf_set_length_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: int64,
+    ) -> c_int;
+    fn mxf_set_uint8_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: uint8,
+    ) -> c_int;
+    fn mxf_set_boolean_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: uint8,
+    ) -> c_int;
+    fn mxf_set_uint16_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: uint16,
+    ) -> c_int;
+    fn mxf_set_uint32_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: uint32,
+    ) -> c_int;
+    fn mxf_set_int32_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: int32,
+    ) -> c_int;
+    fn mxf_set_int16_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: int16,
+    ) -> c_int;
+    fn mxf_set_int64_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: int64,
+    ) -> c_int;
+    fn mxf_set_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *const uint8,
+        len: uint16,
+    ) -> c_int;
+    fn mxf_set_timestamp_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *const MXFTimestamp,
+    ) -> c_int;
+    fn mxf_alloc_array_item_elements(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        element_len: uint32,
+        count: uint32,
+        elements: *mut *mut uint8,
+    ) -> c_int;
+    fn mxf_set_strongref_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *const MXFMetadataSet,
+    ) -> c_int;
+    fn mxf_add_array_item_strongref(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *const MXFMetadataSet,
+    ) -> c_int;
     fn mxf_find_singular_set_by_key(
         headerdata: *mut MXFHeaderMetadata,
         mxfkey: *const MXFKey,
@@ -1453,11 +3571,26 @@ extern "C" {
         mxfkey: *const MXFKey,
         value: *mut uint32,
     ) -> c_int;
+    fn mxf_get_boolean_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *mut uint8,
+    ) -> c_int;
+    fn mxf_get_int16_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *mut int16,
+    ) -> c_int;
     fn mxf_get_int32_item(
         dataset: *mut MXFMetadataSet,
         mxfkey: *const MXFKey,
         value: *mut int32,
     ) -> c_int;
+    fn mxf_get_int64_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        value: *mut int64,
+    ) -> c_int;
     fn mxf_get_umid_item(
         dataset: *mut MXFMetadataSet,
         mxfkey: *const MXFKey,
@@ -1499,6 +3632,11 @@ extern "C" {
         mxfkey: *const MXFKey,
         pos: *mut int64,
     ) -> c_int;
+    fn mxf_get_item(
+        dataset: *mut MXFMetadataSet,
+        mxfkey: *const MXFKey,
+        item: *mut *mut MXFMetadataItem,
+    ) -> c_int;
     fn mxf_set_is_subclass_of(set: *mut MXFMetadataSet, mxfkey: *const MXFKey) -> c_int;
 
     /* mxf_data_model.h */
@@ -1506,16 +3644,43 @@ extern "C" {
     fn mxf_finalise_data_model(datamodel: *mut MXFDataModel) -> c_int;
     fn mxf_free_data_model(datamodel: *mut *mut MXFDataModel);
     fn mxf_is_subclass_of(
-        datamodel: *mut MXFDataModel,
+        datamodel: *const MXFDataModel,
         setkey: *const MXFKey,
         mxfkey: *const MXFKey,
     ) -> c_int;
+    fn mxf_find_set_def(
+        datamodel: *const MXFDataModel,
+        mxfkey: *const MXFKey,
+        set_def: *mut *mut MXFSetDef,
+    ) -> c_int;
+    fn mxf_find_item_def(
+        datamodel: *const MXFDataModel,
+        mxfkey: *const MXFKey,
+        item_def: *mut *mut MXFItemDef,
+    ) -> c_int;
+    fn mxf_get_item_def_type(datamodel: *const MXFDataModel, type_id: c_uint) -> *mut MXFItemType;
+    fn mxf_register_set_def(
+        datamodel: *mut MXFDataModel,
+        name: *const c_char,
+        parent_key: *const MXFKey,
+        mxfkey: *const MXFKey,
+    ) -> c_int;
+    fn mxf_register_item_def(
+        datamodel: *mut MXFDataModel,
+        name: *const c_char,
+        set_key: *const MXFKey,
+        mxfkey: *const MXFKey,
+        tag: uint16,
+        type_id: c_uint,
+        is_required: c_int,
+    ) -> c_int;
 
     /* mxf_label_and_keys.h */
     fn mxf_is_picture(key: *const MXFKey) -> c_int;
     fn mxf_is_sound(key: *const MXFKey) -> c_int;
     fn mxf_is_timecode(key: *const MXFKey) -> c_int;
     fn mxf_is_op_atom(mxful: *const MXFKey) -> c_int;
+    fn mxf_is_gc_essence_element(mxfkey: *const MXFKey) -> c_int;
 
     /* mxf_list.h */
     fn mxf_free_list(list: *mut *mut MXFList);
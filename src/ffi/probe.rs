@@ -0,0 +1,99 @@
+use crate::ffi::compare_length;
+use crate::ffi::consts::*;
+use crate::ffi::mxf::*;
+use crate::ffi::AvidMXFInfo;
+use num_rational::Rational32;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ProbeInfo {
+    pub operational_pattern: MXFKey,
+    pub is_op_atom: bool,
+    pub material_package_uid: Option<MXFUmid>,
+    pub file_source_package_uid: Option<MXFUmid>,
+    pub clip_name: Option<String>,
+    /* Edit rate and duration of the longest material track */
+    pub edit_rate: Option<Rational32>,
+    pub duration: Option<i64>,
+}
+
+impl ProbeInfo {
+    pub fn from_file(filename: &Path) -> Result<ProbeInfo, String> {
+        let model = Arc::new(DataModel::new()?);
+        ProbeInfo::from_file_with_model(filename, &model)
+    }
+
+    pub fn from_file_with_model(
+        filename: &Path,
+        model: &Arc<DataModel>,
+    ) -> Result<ProbeInfo, String> {
+        let mut info = ProbeInfo::default();
+
+        /* Open file without the dictionary */
+        let file = MXFFile::probe_with_model(filename, model)?;
+        info.operational_pattern = file.headerpartition().operational_pattern;
+        info.is_op_atom = info.operational_pattern.is_op_atom();
+
+        /* Get material package set */
+        let material_package_set = file
+            .headerdata()
+            .find_singular_set_by_key(&G_MATERIALPACKAGE_SET_KEY)?;
+        info.material_package_uid =
+            material_package_set.get_umid(&G_GENERICPACKAGE_PACKAGEUID_ITEM_KEY);
+        if material_package_set.has_item(&G_GENERICPACKAGE_NAME_ITEM_KEY) {
+            info.clip_name = material_package_set.get_string(&G_GENERICPACKAGE_NAME_ITEM_KEY);
+        }
+
+        /* Get the longest material track, data definitions are not available to tell picture
+         * and sound tracks apart */
+        let mut array_iter = material_package_set
+            .get_package_tracks()
+            .ok_or("Could not read package tracks.".to_string())?;
+        array_iter.headerdata = Some(file.headerdata());
+        for track_set in array_iter {
+            let edit_rate = match track_set.get_rational(&G_TRACK_EDITRATE_ITEM_KEY) {
+                Some(x) => x,
+                None => continue,
+            };
+            let sequence_set = match track_set.get_strongref(&G_GENERICTRACK_SEQUENCE_ITEM_KEY) {
+                Some(x) => x,
+                None => continue,
+            };
+            let duration = match sequence_set.get_length(&G_STRUCTURALCOMPONENT_DURATION_ITEM_KEY) {
+                Some(x) => x,
+                None => continue,
+            };
+            let is_longer = match (info.edit_rate.as_ref(), info.duration) {
+                (Some(max_edit_rate), Some(max_duration)) => {
+                    compare_length(&edit_rate, duration, max_edit_rate, max_duration) > 0
+                }
+                _ => true,
+            };
+            if is_longer {
+                info.edit_rate = Some(edit_rate);
+                info.duration = Some(duration);
+            }
+        }
+
+        /* Get the top level file source package */
+        if let Some(file_source_package_set) = file.headerdata().get_top_file_package() {
+            info.file_source_package_uid =
+                file_source_package_set.get_umid(&G_GENERICPACKAGE_PACKAGEUID_ITEM_KEY);
+        }
+
+        Ok(info)
+    }
+}
+
+impl AvidMXFInfo {
+    /* Reads only the partition pack, preface and packages, for scanning many files */
+    pub fn probe(filename: &Path) -> Result<ProbeInfo, String> {
+        ProbeInfo::from_file(filename)
+    }
+
+    /* Probing many files is dominated by loading the data model unless it is shared */
+    pub fn probe_with_model(filename: &Path, model: &Arc<DataModel>) -> Result<ProbeInfo, String> {
+        ProbeInfo::from_file_with_model(filename, model)
+    }
+}
@@ -0,0 +1,232 @@
+use crate::ffi::consts::*;
+use crate::ffi::mxf::*;
+use num_rational::Rational32;
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageDescriptorType {
+    Material,
+    Picture,
+    Sound,
+    Data,
+    Multiple,
+    Tape,
+    Import,
+    Recording,
+    Unknown,
+}
+
+impl Default for PackageDescriptorType {
+    fn default() -> PackageDescriptorType {
+        PackageDescriptorType::Unknown
+    }
+}
+
+impl PackageDescriptorType {
+    /* File packages describe the essence stored in a file */
+    pub fn is_file(&self) -> bool {
+        match *self {
+            PackageDescriptorType::Picture
+            | PackageDescriptorType::Sound
+            | PackageDescriptorType::Data
+            | PackageDescriptorType::Multiple => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for PackageDescriptorType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PackageDescriptorType::Material => write!(f, "Material"),
+            PackageDescriptorType::Picture => write!(f, "Picture"),
+            PackageDescriptorType::Sound => write!(f, "Sound"),
+            PackageDescriptorType::Data => write!(f, "Data"),
+            PackageDescriptorType::Multiple => write!(f, "Multiple"),
+            PackageDescriptorType::Tape => write!(f, "Tape"),
+            PackageDescriptorType::Import => write!(f, "Import"),
+            PackageDescriptorType::Recording => write!(f, "Recording"),
+            PackageDescriptorType::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/* One package in the derivation chain. The track, start position and edit rate are those of the
+ * source clip referencing the next package in the chain. */
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SourcePackageHop {
+    pub package_uid: MXFUmid,
+    pub name: Option<String>,
+    pub descriptor_type: PackageDescriptorType,
+    pub track_id: Option<u32>,
+    pub start_position: Option<i64>,
+    pub edit_rate: Option<Rational32>,
+}
+
+fn descriptor_type(file: &MXFFile, package_set: &mut MXFMetadataSet) -> PackageDescriptorType {
+    if file
+        .datamodel()
+        .is_subclass_of(&package_set.key, &G_MATERIALPACKAGE_SET_KEY)
+    {
+        return PackageDescriptorType::Material;
+    }
+    if !package_set.has_item(&G_SOURCEPACKAGE_DESCRIPTOR_ITEM_KEY) {
+        return PackageDescriptorType::Unknown;
+    }
+    let descriptor_set = match package_set.get_strongref(&G_SOURCEPACKAGE_DESCRIPTOR_ITEM_KEY) {
+        Some(x) => x,
+        /* Dark set not registered in dictionary. */
+        None => return PackageDescriptorType::Unknown,
+    };
+
+    let types = [
+        (&G_TAPEDESCRIPTOR_SET_KEY, PackageDescriptorType::Tape),
+        (&G_IMPORTDESCRIPTOR_SET_KEY, PackageDescriptorType::Import),
+        (
+            &G_RECORDINGDESCRIPTOR_SET_KEY,
+            PackageDescriptorType::Recording,
+        ),
+        (
+            &G_MULTIPLEDESCRIPTOR_SET_KEY,
+            PackageDescriptorType::Multiple,
+        ),
+        (
+            &G_GENERICPICTUREESSENCEDESCRIPTOR_SET_KEY,
+            PackageDescriptorType::Picture,
+        ),
+        (
+            &G_GENERICSOUNDESSENCEDESCRIPTOR_SET_KEY,
+            PackageDescriptorType::Sound,
+        ),
+        (
+            &G_GENERICDATAESSENCEDESCRIPTOR_SET_KEY,
+            PackageDescriptorType::Data,
+        ),
+    ];
+    for &(key, descriptor_type) in types.iter() {
+        if file.datamodel().is_subclass_of(&descriptor_set.key, key) {
+            return descriptor_type;
+        }
+    }
+
+    PackageDescriptorType::Unknown
+}
+
+/* Finds the picture or sound track with the given track id, or the first one if no track id is
+ * given, and its source clip. A track whose source clip references the preferred package wins. */
+fn find_source_clip(
+    file: &MXFFile,
+    package_set: &mut MXFMetadataSet,
+    track_id: Option<u32>,
+    preferred_package_uid: Option<&MXFUmid>,
+) -> Option<(&'static mut MXFMetadataSet, &'static mut MXFMetadataSet)> {
+    let mut array_iter = package_set.get_package_tracks()?;
+    array_iter.headerdata = Some(file.headerdata());
+    let mut first = None;
+    for track_set in array_iter {
+        let mut datadef = match track_set.get_track_datadef() {
+            Some(x) => x,
+            None => continue,
+        };
+        /* Some Avid files have a weak reference to a data definition instead of a UL */
+        if !datadef.is_picture() && !datadef.is_sound() && !datadef.is_timecode() {
+            datadef = match file.headerdata().get_data_def(&datadef) {
+                Some(x) => x,
+                None => continue,
+            };
+        }
+        if !datadef.is_picture() && !datadef.is_sound() {
+            continue;
+        }
+        if track_id.is_some() && track_set.get_uint32(&G_GENERICTRACK_TRACKID_ITEM_KEY) != track_id
+        {
+            continue;
+        }
+        let source_clip_set = match track_set.get_single_track_component(&G_SOURCECLIP_SET_KEY) {
+            Some(x) => x,
+            None => continue,
+        };
+        let references_preferred = match preferred_package_uid {
+            Some(uid) => {
+                source_clip_set
+                    .get_umid(&G_SOURCECLIP_SOURCEPACKAGEID_ITEM_KEY)
+                    .as_ref()
+                    == Some(uid)
+            }
+            None => true,
+        };
+        if references_preferred {
+            return Some((track_set, source_clip_set));
+        }
+        if first.is_none() {
+            first = Some((track_set, source_clip_set));
+        }
+    }
+
+    first
+}
+
+pub fn read_source_chain(
+    file: &MXFFile,
+    material_package_set: &mut MXFMetadataSet,
+    file_source_package_uid: Option<&MXFUmid>,
+) -> Vec<SourcePackageHop> {
+    let mut chain = Vec::new();
+    let mut visited = HashSet::new();
+    let mut package_set = material_package_set;
+    let mut track_id = None;
+    let mut preferred_package_uid = file_source_package_uid;
+
+    loop {
+        let mut hop = SourcePackageHop::default();
+        hop.package_uid = match package_set.get_umid(&G_GENERICPACKAGE_PACKAGEUID_ITEM_KEY) {
+            Some(x) => x,
+            None => break,
+        };
+        /* Guard against packages referencing each other */
+        if !visited.insert(hop.package_uid) {
+            break;
+        }
+        if package_set.has_item(&G_GENERICPACKAGE_NAME_ITEM_KEY) {
+            hop.name = package_set.get_string(&G_GENERICPACKAGE_NAME_ITEM_KEY);
+        }
+        hop.descriptor_type = descriptor_type(file, package_set);
+
+        let (track_set, source_clip_set) =
+            match find_source_clip(file, package_set, track_id, preferred_package_uid) {
+                Some(x) => x,
+                None => {
+                    chain.push(hop);
+                    break;
+                }
+            };
+        hop.track_id = track_set.get_uint32(&G_GENERICTRACK_TRACKID_ITEM_KEY);
+        hop.edit_rate = track_set.get_rational(&G_TRACK_EDITRATE_ITEM_KEY);
+        hop.start_position = source_clip_set.get_position(&G_SOURCECLIP_STARTPOSITION_ITEM_KEY);
+        chain.push(hop);
+
+        /* Follow the source clip to the referenced package */
+        let source_package_uid =
+            match source_clip_set.get_umid(&G_SOURCECLIP_SOURCEPACKAGEID_ITEM_KEY) {
+                Some(x) => x,
+                None => break,
+            };
+        if source_package_uid == MXFUmid::default() {
+            /* At the end of the chain */
+            break;
+        }
+        package_set = match file
+            .headerdata()
+            .get_referenced_package(&source_package_uid)
+        {
+            Some(x) => x,
+            /* The referenced package is not in this file */
+            None => break,
+        };
+        track_id = source_clip_set.get_uint32(&G_SOURCECLIP_SOURCETRACKID_ITEM_KEY);
+        preferred_package_uid = None;
+    }
+
+    chain
+}
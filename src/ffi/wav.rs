@@ -0,0 +1,163 @@
+use crate::ffi::convert_length;
+use crate::ffi::essence::EssenceReader;
+use crate::ffi::mxf::*;
+use crate::ffi::AvidMXFInfo;
+use num_rational::Rational32;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/* Edit units read at once, one second of 48kHz audio */
+const READ_BLOCK_SIZE: u32 = 48000;
+/* Size of the bext chunk without coding history, see EBU Tech 3285 */
+const BEXT_SIZE: usize = 602;
+
+/* BWF and AES3 clip-wrapped elements of the generic sound mapping (SMPTE 382) both carry
+ * little-endian PCM as in a WAV data chunk. The AES3 subframes of the D-10 mapping (SMPTE 386)
+ * are in D-10 containers, which are not accepted. */
+fn is_clip_wrapped_pcm(label: &MXFKey) -> bool {
+    let octets = label.as_bytes();
+    octets[..7] == [0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01]
+        && octets[8..14] == [0x0d, 0x01, 0x03, 0x01, 0x02, 0x06]
+        && (octets[14] == 0x02 || octets[14] == 0x04)
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<(), String> {
+    writer
+        .write_all(bytes)
+        .map_err(|e| format!("Could not write WAV file: {}.", e))
+}
+
+/* Writes a fixed size field, truncating or zero padding the value */
+fn write_padded<W: Write>(writer: &mut W, value: &[u8], size: usize) -> Result<(), String> {
+    let mut field = vec![0; size];
+    let len = value.len().min(size);
+    field[..len].copy_from_slice(&value[..len]);
+    write_bytes(writer, &field)
+}
+
+impl AvidMXFInfo {
+    /* Sample offset of the start timecode since midnight */
+    fn time_reference(&self, sample_rate: &Rational32) -> u64 {
+        match self.clip_edit_rate.as_ref() {
+            Some(edit_rate) => convert_length(sample_rate, edit_rate, self.start_timecode) as u64,
+            None => 0,
+        }
+    }
+
+    /* Writes the PCM essence of an audio file as a BWF WAV file */
+    pub fn write_wav<W: Write>(&self, filename: &Path, writer: &mut W) -> Result<(), String> {
+        let sample_rate = self
+            .audio_sampling_rate
+            .ok_or("Not an audio file.".to_string())?;
+        let channel_count = self
+            .channel_count
+            .ok_or("Could not read channel count.".to_string())? as u16;
+        let quantization_bits = self
+            .quantization_bits
+            .ok_or("Could not read quantization bits.".to_string())?;
+        match self.essence_container_label.as_ref() {
+            Some(label) if is_clip_wrapped_pcm(label) => {}
+            _ => return Err("Only clip-wrapped BWF and AES3 audio is supported.".to_string()),
+        }
+        if *sample_rate.denom() != 1 || channel_count == 0 {
+            return Err("Unsupported audio sampling rate or channel count.".to_string());
+        }
+        /* Samples are stored in whole bytes */
+        let block_align = self
+            .block_align
+            .unwrap_or((channel_count as u32 * ((quantization_bits + 7) / 8)) as u16);
+        let bits_per_sample = block_align / channel_count * 8;
+
+        let file = MXFFile::open(filename, true)?;
+        let reader = EssenceReader::with_block_size(&file, READ_BLOCK_SIZE)?;
+        let data_size = reader.index_table().essence_length;
+        if data_size + BEXT_SIZE as u64 + 48 > u64::from(u32::max_value()) {
+            return Err("Audio is too large for a WAV file.".to_string());
+        }
+        let data_size = data_size as u32;
+
+        /* RIFF header */
+        write_bytes(writer, b"RIFF")?;
+        write_bytes(
+            writer,
+            &(4 + 8 + BEXT_SIZE as u32 + 8 + 16 + 8 + data_size + data_size % 2).to_le_bytes(),
+        )?;
+        write_bytes(writer, b"WAVE")?;
+
+        /* Broadcast extension with the clip name and start timecode */
+        write_bytes(writer, b"bext")?;
+        write_bytes(writer, &(BEXT_SIZE as u32).to_le_bytes())?;
+        write_padded(
+            writer,
+            self.clip_name
+                .as_ref()
+                .map_or(&b""[..], |name| name.as_bytes()),
+            256,
+        )?;
+        write_padded(writer, b"", 32)?;
+        write_padded(writer, b"", 32)?;
+        match self.clip_created {
+            Some(created) => {
+                write_bytes(writer, created.format("%Y-%m-%d").to_string().as_bytes())?;
+                write_bytes(writer, created.format("%H:%M:%S").to_string().as_bytes())?;
+            }
+            None => write_padded(writer, b"", 18)?,
+        }
+        write_bytes(writer, &self.time_reference(&sample_rate).to_le_bytes())?;
+        write_bytes(writer, &1u16.to_le_bytes())?;
+        write_padded(
+            writer,
+            &self.material_package_uid.unwrap_or_default().as_bytes(),
+            64,
+        )?;
+        /* Loudness values and reserved */
+        write_padded(writer, b"", 10 + 180)?;
+
+        /* Format */
+        write_bytes(writer, b"fmt ")?;
+        write_bytes(writer, &16u32.to_le_bytes())?;
+        write_bytes(writer, &1u16.to_le_bytes())?;
+        write_bytes(writer, &channel_count.to_le_bytes())?;
+        write_bytes(writer, &(*sample_rate.numer() as u32).to_le_bytes())?;
+        write_bytes(
+            writer,
+            &(*sample_rate.numer() as u32 * u32::from(block_align)).to_le_bytes(),
+        )?;
+        write_bytes(writer, &block_align.to_le_bytes())?;
+        write_bytes(writer, &bits_per_sample.to_le_bytes())?;
+
+        /* Samples */
+        write_bytes(writer, b"data")?;
+        write_bytes(writer, &data_size.to_le_bytes())?;
+        let mut written = 0;
+        for block in reader {
+            let block = block?;
+            written += block.data.len() as u64;
+            write_bytes(writer, &block.data)?;
+        }
+        if written != u64::from(data_size) {
+            return Err("Could not read all audio samples.".to_string());
+        }
+        if data_size % 2 == 1 {
+            /* Chunks are word aligned */
+            write_bytes(writer, &[0])?;
+        }
+
+        Ok(())
+    }
+
+    /* Extracts the PCM essence of an audio file to a BWF WAV file */
+    pub fn extract_wav(filename: &Path, output: &Path) -> Result<AvidMXFInfo, String> {
+        let info = AvidMXFInfo::from_file(filename)?;
+        let file =
+            File::create(output).map_err(|e| format!("Could not create WAV file: {}.", e))?;
+        let mut writer = BufWriter::new(file);
+        info.write_wav(filename, &mut writer)?;
+        writer
+            .flush()
+            .map_err(|e| format!("Could not write WAV file: {}.", e))?;
+
+        Ok(info)
+    }
+}
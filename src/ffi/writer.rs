@@ -0,0 +1,143 @@
+use crate::ffi::consts::*;
+use crate::ffi::mxf::*;
+use crate::ffi::AvidMXFInfo;
+use std::path::Path;
+use std::sync::Arc;
+
+/* Project name mob attribute written by older Avid versions */
+const PROJECT_NAME_ATTRIBUTE: &str = "_PJ";
+
+/* Metadata to change in place. Fields left unset keep their value, and tagged values replace the
+ * value of an existing name or are added. */
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MetadataUpdate {
+    pub clip_name: Option<String>,
+    pub project_name: Option<String>,
+    pub user_comments: Vec<(String, String)>,
+    pub mob_attributes: Vec<(String, String)>,
+}
+
+/* Replaces the value of the named tagged value in the list, returning false if there is none */
+fn replace_tagged_value(
+    file: &MXFFile,
+    package_set: &mut MXFMetadataSet,
+    list_key: &MXFKey,
+    name: &str,
+    value: &str,
+) -> Result<bool, String> {
+    if !package_set.has_item(list_key) {
+        return Ok(false);
+    }
+
+    let array_iter = package_set
+        .initialize_array_iterator(list_key)
+        .ok_or("Could not read array item iterator.".to_string())?;
+    for item in array_iter {
+        let tagged_value_set = match file.headerdata().get_strongref(item) {
+            Some(x) => x,
+            /* Dark set not registered in dictionary. */
+            None => continue,
+        };
+        if tagged_value_set.get_string(&G_TAGGEDVALUE_NAME_ITEM_KEY) == Some(name.to_string()) {
+            tagged_value_set.set_indirect_string(&G_TAGGEDVALUE_VALUE_ITEM_KEY, value)?;
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+impl MetadataUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.clip_name.is_none()
+            && self.project_name.is_none()
+            && self.user_comments.is_empty()
+            && self.mob_attributes.is_empty()
+    }
+
+    /* Applies the update to the material package and preface of the file's header metadata */
+    pub fn apply(&self, file: &MXFFile) -> Result<(), String> {
+        let material_package_set = file
+            .headerdata()
+            .find_singular_set_by_key(&G_MATERIALPACKAGE_SET_KEY)?;
+
+        if let Some(ref clip_name) = self.clip_name {
+            material_package_set.set_string(&G_GENERICPACKAGE_NAME_ITEM_KEY, clip_name)?;
+        }
+
+        if let Some(ref project_name) = self.project_name {
+            let preface_set = file
+                .headerdata()
+                .find_singular_set_by_key(&G_PREFACE_SET_KEY)?;
+            preface_set.set_string(&G_PREFACE_PROJECTNAME_ITEM_KEY, project_name)?;
+            /* Keep the fallback read when the preface has no project name consistent */
+            replace_tagged_value(
+                file,
+                material_package_set,
+                &G_GENERICPACKAGE_MOBATTRIBUTELIST_ITEM_KEY,
+                PROJECT_NAME_ATTRIBUTE,
+                project_name,
+            )?;
+        }
+
+        for &(ref name, ref value) in &self.user_comments {
+            if !replace_tagged_value(
+                file,
+                material_package_set,
+                &G_GENERICPACKAGE_USERCOMMENTS_ITEM_KEY,
+                name,
+                value,
+            )? {
+                material_package_set.attach_user_comment(name, value)?;
+            }
+        }
+
+        for &(ref name, ref value) in &self.mob_attributes {
+            if !replace_tagged_value(
+                file,
+                material_package_set,
+                &G_GENERICPACKAGE_MOBATTRIBUTELIST_ITEM_KEY,
+                name,
+                value,
+            )? {
+                material_package_set.attach_mob_attribute(name, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AvidMXFInfo {
+    /* Updates the header metadata of every partition carrying a copy. Nothing is written unless
+     * all updated copies fit in the space of the ones they replace, including fill. Sets not
+     * registered in the data model are copied over unchanged. */
+    pub fn update_metadata(
+        filename: &Path,
+        update: &MetadataUpdate,
+    ) -> Result<AvidMXFInfo, String> {
+        if !update.is_empty() {
+            let model = Arc::new(DataModel::new()?);
+            let mut file = MXFFile::open_modify(filename, &model)?;
+            let offsets: Vec<u64> = file
+                .partitions()?
+                .into_iter()
+                .filter(|partition| partition.header_byte_count > 0)
+                .map(|partition| partition.this_partition)
+                .collect();
+
+            let mut encoded = Vec::with_capacity(offsets.len());
+            for offset in offsets {
+                file.read_partition_metadata(offset)?;
+                let original = file.header_metadata_bytes()?;
+                update.apply(&file)?;
+                encoded.push(file.encode_header_metadata_with_dark_sets(&original)?);
+            }
+            for (offset, data) in encoded {
+                file.write_bytes(offset, &data)?;
+            }
+        }
+
+        AvidMXFInfo::from_file(filename)
+    }
+}
@@ -8,15 +8,63 @@ mod macros;
 mod ffi;
 
 pub type MXFInfo = ffi::AvidMXFInfo;
+pub type MXFFile = ffi::MXFFile;
+pub type DataModel = ffi::DataModel;
 pub type MXFKey = ffi::MXFKey;
 pub type MXFUmid = ffi::MXFUmid;
+pub type EssenceGroup = ffi::EssenceGroup;
+pub type EssenceGroupChoice = ffi::EssenceGroupChoice;
+pub type Locator = ffi::Locator;
+pub type LocatorKind = ffi::LocatorKind;
+pub type Marker = ffi::Marker;
+pub type Timecode = ffi::Timecode;
+pub type DarkMetadata = ffi::DarkMetadata;
+pub type DarkSet = ffi::DarkSet;
+pub type DarkItem = ffi::DarkItem;
+pub type Dictionary = ffi::Dictionary;
+pub type Definition = ffi::Definition;
+pub type MetaDictionary = ffi::MetaDictionary;
+pub type ClassDefinition = ffi::ClassDefinition;
+pub type PropertyDefinition = ffi::PropertyDefinition;
+pub type MetadataDump = ffi::MetadataDump;
+pub type DumpSet = ffi::DumpSet;
+pub type DumpItem = ffi::DumpItem;
+pub type DumpValue = ffi::DumpValue;
+pub type EssenceFrame = ffi::EssenceFrame;
+pub type EssenceReader<'a> = ffi::EssenceReader<'a>;
+pub type IndexTable = ffi::IndexTable;
+pub type IndexTableSegment = ffi::IndexTableSegment;
+pub type IndexEntry = ffi::IndexEntry;
+pub type DeltaEntry = ffi::DeltaEntry;
+pub type MediaKind = ffi::MediaKind;
+pub type MetadataSource = ffi::MetadataSource;
+pub type MetadataUpdate = ffi::MetadataUpdate;
+pub type OpAtomBuilder = ffi::OpAtomBuilder;
+pub type PartitionInfo = ffi::PartitionInfo;
+pub type PartitionKind = ffi::PartitionKind;
+pub type ProbeInfo = ffi::ProbeInfo;
+pub type PackageDescriptorType = ffi::PackageDescriptorType;
+pub type SourcePackageHop = ffi::SourcePackageHop;
+pub type RGBColor = ffi::RGBColor;
+pub type ArchiveMetadata = ffi::ArchiveMetadata;
+pub type InfaxRecord = ffi::InfaxRecord;
+pub type PSEFailure = ffi::PSEFailure;
+pub type VTRError = ffi::VTRError;
+pub type DigiBetaDropout = ffi::DigiBetaDropout;
+pub type TimecodeBreak = ffi::TimecodeBreak;
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::NaiveDate;
+    #[cfg(feature = "tokio")]
+    use futures::stream::StreamExt;
     use num_rational::Rational32;
+    use std::env;
+    use std::fs;
     use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::thread;
 
     #[test]
     fn can_retrieve_from_video_file() {
@@ -72,7 +120,24 @@ mod tests {
             Some(essence_label),
             "essence label"
         );
+        assert_eq!(
+            mxf.essence_container_labels,
+            vec![essence_label],
+            "essence labels"
+        );
+        assert_eq!(
+            mxf.essence_container_mismatch, false,
+            "essence label mismatch"
+        );
         assert_eq!(mxf.is_renderfile(), false, "render file");
+        assert_eq!(mxf.media_kind(), MediaKind::Imported, "media kind");
+        assert_eq!(
+            mxf.sample_rate,
+            Some(Rational32::new(50, 1)),
+            "descriptor sample rate"
+        );
+        assert_eq!(mxf.container_duration, Some(49), "container duration");
+        assert_eq!(mxf.is_truncated(), false, "truncated");
         assert_eq!(
             mxf.clip_edit_rate,
             Some(Rational32::new(50, 1)),
@@ -86,6 +151,1546 @@ mod tests {
             Some("domdom.mov".to_string()),
             "physical package name"
         );
+        assert_eq!(mxf.source_chain.len(), 3, "source chain length");
+        assert_eq!(
+            mxf.source_chain[0].descriptor_type,
+            PackageDescriptorType::Material,
+            "source chain material package"
+        );
+        assert_eq!(
+            mxf.source_chain[1].package_uid,
+            mxf.file_source_package_uid.unwrap(),
+            "source chain file package"
+        );
+        assert_eq!(
+            mxf.source_chain[2].descriptor_type,
+            PackageDescriptorType::Import,
+            "source chain import package"
+        );
+    }
+
+    #[test]
+    fn has_no_archive_metadata_in_avid_file() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.V159CD0127V.mxf");
+        let archive = ArchiveMetadata::from_file(filename.as_path()).unwrap();
+
+        assert_eq!(archive.is_archive_file(), false, "archive file");
+        assert_eq!(archive.pse_failures.len(), 0, "pse failures");
+        assert_eq!(archive.timecode_breaks.len(), 0, "timecode breaks");
+    }
+
+    #[test]
+    fn can_read_archive_metadata_from_footer() {
+        let filename = temp_path("builder-archive");
+        let archive = ArchiveMetadata {
+            infax: Some(InfaxRecord {
+                format: Some("D3".to_string()),
+                programme_title: Some("Synthetic".to_string()),
+                transmission_date: Some(NaiveDate::from_ymd(1982, 3, 4)),
+                duration: Some(1500),
+                spool_number: Some("LDL123456".to_string()),
+                item_number: Some(1),
+                ..InfaxRecord::default()
+            }),
+            pse_failures: vec![PSEFailure {
+                position: 3,
+                red_flash: 500,
+                spatial_pattern: 0,
+                luminance_flash: 1200,
+                extended_failure: true,
+            }],
+            vtr_errors: vec![VTRError {
+                position: 5,
+                error_code: 0x11,
+            }],
+            digibeta_dropouts: vec![DigiBetaDropout {
+                position: 7,
+                strength: 42,
+            }],
+            timecode_breaks: vec![TimecodeBreak {
+                position: 2,
+                timecode_type: 1,
+            }],
+            pse_failure_count: Some(1),
+            vtr_error_count: Some(1),
+            digibeta_dropout_count: Some(1),
+            timecode_break_count: Some(1),
+        };
+        OpAtomBuilder::picture(Rational32::new(25, 1), 10)
+            .open_header(true)
+            .archive_metadata(archive.clone())
+            .write(filename.as_path())
+            .unwrap();
+
+        let read = ArchiveMetadata::from_file(filename.as_path()).unwrap();
+        assert!(read.is_archive_file(), "archive file");
+        assert_eq!(read, archive, "archive metadata from footer");
+        fs::remove_file(&filename).unwrap();
+    }
+
+    #[test]
+    fn can_read_dictionaries_of_avid_file() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.V159CD0127V.mxf");
+        let dictionary = Dictionary::from_file(filename.as_path()).unwrap();
+        let metadictionary = MetaDictionary::from_file(filename.as_path()).unwrap();
+
+        assert!(!dictionary.data_definitions.is_empty(), "data definitions");
+        assert!(
+            dictionary
+                .data_definitions
+                .iter()
+                .all(|definition| definition.identification.is_some()),
+            "data definition identifications"
+        );
+        assert!(
+            !metadictionary.class_definitions.is_empty(),
+            "class definitions"
+        );
+        assert!(
+            !metadictionary.type_definitions.is_empty(),
+            "type definitions"
+        );
+
+        let file = MXFFile::open_with_extensions(filename.as_path(), true).unwrap();
+        assert_eq!(
+            MetaDictionary::from_mxf_file(&file).unwrap(),
+            metadictionary,
+            "same meta-dictionary with extensions registered"
+        );
+        assert!(
+            file.dark_metadata().unwrap().sets.len()
+                < MXFFile::open(filename.as_path(), true)
+                    .unwrap()
+                    .dark_metadata()
+                    .unwrap()
+                    .sets
+                    .len(),
+            "extension sets no longer dark"
+        );
+    }
+
+    #[test]
+    fn can_parse_meta_dictionary() {
+        let prefix = [0x06, 0x0e, 0x2b, 0x34];
+        let key = |octets: [u8; 12]| {
+            let mut key = prefix.to_vec();
+            key.extend_from_slice(&octets);
+            key
+        };
+        let local_set = |set_key: Vec<u8>, items: &[(u16, Vec<u8>)]| {
+            let mut value = Vec::new();
+            for &(tag, ref item) in items {
+                value.extend_from_slice(&tag.to_be_bytes());
+                value.extend_from_slice(&(item.len() as u16).to_be_bytes());
+                value.extend_from_slice(item);
+            }
+            let mut data = set_key;
+            data.push(0x83);
+            data.extend_from_slice(&(value.len() as u32).to_be_bytes()[1..]);
+            data.extend(value);
+            data
+        };
+        let batch = |uids: &[[u8; 16]]| {
+            let mut data = (uids.len() as u32).to_be_bytes().to_vec();
+            data.extend_from_slice(&16u32.to_be_bytes());
+            for uid in uids {
+                data.extend_from_slice(uid);
+            }
+            data
+        };
+        let utf16 = |name: &str| {
+            let mut data = Vec::new();
+            for c in name.encode_utf16().chain(Some(0)) {
+                data.extend_from_slice(&c.to_be_bytes());
+            }
+            data
+        };
+        let metadictionary_uid = [0x01; 16];
+        let class_uid = [0x02; 16];
+        let property_uid = [0x03; 16];
+        let class_key = [0x0e, 0x04, 0x01, 0x01, 0x01, 0x01, 0x01, 0x00];
+        let parent_key = [0x0d, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x00];
+        let item_key = [0x0e, 0x04, 0x01, 0x01, 0x01, 0x01, 0x02, 0x00];
+        let extension = |octets: [u8; 8]| {
+            let mut key = [0; 16];
+            key[..8].copy_from_slice(&[0x06, 0x0e, 0x2b, 0x34, 0x02, 0x53, 0x01, 0x01]);
+            key[8..].copy_from_slice(&octets);
+            key
+        };
+
+        let primer_entries = [
+            (
+                0x3c0a,
+                key([0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x15, 0x02, 0, 0, 0, 0]),
+            ),
+            (
+                0x0003,
+                key([
+                    0x01, 0x01, 0x01, 0x02, 0x06, 0x01, 0x01, 0x07, 0x07, 0, 0, 0,
+                ]),
+            ),
+            (
+                0x0006,
+                key([
+                    0x01, 0x01, 0x01, 0x02, 0x06, 0x01, 0x01, 0x07, 0x13, 0, 0, 0,
+                ]),
+            ),
+            (
+                0x0007,
+                key([
+                    0x01, 0x01, 0x01, 0x02, 0x03, 0x02, 0x04, 0x01, 0x02, 0x01, 0, 0,
+                ]),
+            ),
+            (
+                0x0008,
+                key([
+                    0x01, 0x01, 0x01, 0x02, 0x06, 0x01, 0x01, 0x07, 0x01, 0, 0, 0,
+                ]),
+            ),
+            (
+                0x0009,
+                key([
+                    0x01, 0x01, 0x01, 0x02, 0x06, 0x01, 0x01, 0x07, 0x02, 0, 0, 0,
+                ]),
+            ),
+            (
+                0x000a,
+                key([
+                    0x01, 0x01, 0x01, 0x02, 0x06, 0x01, 0x01, 0x07, 0x03, 0, 0, 0,
+                ]),
+            ),
+            (
+                0x000d,
+                key([
+                    0x01, 0x01, 0x01, 0x02, 0x06, 0x01, 0x01, 0x07, 0x05, 0, 0, 0,
+                ]),
+            ),
+        ];
+        let mut primer = (primer_entries.len() as u32).to_be_bytes().to_vec();
+        primer.extend_from_slice(&18u32.to_be_bytes());
+        for &(tag, ref item_key) in primer_entries.iter() {
+            primer.extend_from_slice(&(tag as u16).to_be_bytes());
+            primer.extend_from_slice(item_key);
+        }
+        let mut data = key([
+            0x02, 0x05, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x01, 0x05, 0x01, 0,
+        ]);
+        data.push(0x83);
+        data.extend_from_slice(&(primer.len() as u32).to_be_bytes()[1..]);
+        data.extend(primer);
+
+        data.extend(local_set(
+            key([
+                0x02, 0x53, 0x01, 0x01, 0x0d, 0x01, 0x01, 0x01, 0x02, 0x25, 0, 0,
+            ]),
+            &[
+                (0x3c0a, metadictionary_uid.to_vec()),
+                (0x0003, batch(&[class_uid])),
+            ],
+        ));
+        data.extend(local_set(
+            key([
+                0x02, 0x53, 0x01, 0x01, 0x0d, 0x01, 0x01, 0x01, 0x02, 0x01, 0, 0,
+            ]),
+            &[
+                (0x3c0a, class_uid.to_vec()),
+                (0x0006, extension(class_key).to_vec()),
+                (0x0007, utf16("Extension")),
+                (0x0008, extension(parent_key).to_vec()),
+                (0x000a, vec![1]),
+                (0x0009, batch(&[property_uid])),
+            ],
+        ));
+        data.extend(local_set(
+            key([
+                0x02, 0x53, 0x01, 0x01, 0x0d, 0x01, 0x01, 0x01, 0x02, 0x02, 0, 0,
+            ]),
+            &[
+                (0x3c0a, property_uid.to_vec()),
+                (0x0006, extension(item_key).to_vec()),
+                (0x0007, utf16("Flag")),
+                (0x000d, vec![0xff, 0x01]),
+            ],
+        ));
+
+        assert_eq!(
+            MetaDictionary::from_header_metadata(&data),
+            MetaDictionary {
+                class_definitions: vec![ClassDefinition {
+                    identification: Some(MXFKey::from(extension(class_key))),
+                    name: Some("Extension".to_string()),
+                    description: None,
+                    parent_class: Some(MXFKey::from(extension(parent_key))),
+                    is_concrete: Some(true),
+                    properties: vec![PropertyDefinition {
+                        identification: Some(MXFKey::from(extension(item_key))),
+                        name: Some("Flag".to_string()),
+                        local_identification: Some(0xff01),
+                        ..PropertyDefinition::default()
+                    }],
+                }],
+                type_definitions: vec![],
+            },
+            "meta-dictionary"
+        );
+        assert_eq!(
+            MetaDictionary::from_header_metadata(&data[..data.len() - 1]).class_definitions[0]
+                .properties
+                .len(),
+            0,
+            "truncated property definition"
+        );
+    }
+
+    #[test]
+    fn can_name_labels() {
+        let essence_label = MXFKey::new(
+            0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x11,
+            0x02, 0x00,
+        );
+        let picture_label = MXFKey::new(
+            0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x71, 0x12,
+            0x00, 0x00,
+        );
+        let unknown_label = MXFKey::new(
+            0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x02, 0x7e, 0x00,
+            0x00, 0x00,
+        );
+
+        assert_eq!(essence_label.name(), Some("VC-3 (DNxHD) Clip-wrapped"));
+        assert_eq!(picture_label.describe(), "DNxHD 1252".to_string());
+        let frame_wrapped = MXFKey::from_name("VC-3 (DNxHD) Frame-wrapped").unwrap();
+        assert_eq!(
+            frame_wrapped.describe_with_coding(&picture_label),
+            "DNxHD 1252 Frame-wrapped".to_string()
+        );
+        assert_eq!(
+            essence_label.describe_with_coding(&unknown_label),
+            "VC-3 (DNxHD) Clip-wrapped".to_string()
+        );
+        assert_eq!(MXFKey::from_name("dnxhd 1252"), Some(picture_label));
+        assert_eq!(unknown_label.name(), None);
+        assert_eq!(
+            unknown_label.describe(),
+            "unknown picture coding 060e2b34.0401010a.04010202.7e000000".to_string()
+        );
+    }
+
+    #[test]
+    fn can_format_and_parse_keys() {
+        let key = MXFKey::from([
+            0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x0a, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x11,
+            0x02, 0x00,
+        ]);
+        let other_version = MXFKey::new(
+            0x06, 0x0e, 0x2b, 0x34, 0x04, 0x01, 0x01, 0x01, 0x0d, 0x01, 0x03, 0x01, 0x02, 0x11,
+            0x02, 0x00,
+        );
+        let urn = "urn:smpte:ul:060e2b34.0401010a.0d010301.02110200";
+
+        assert_eq!(key.to_string(), urn, "urn");
+        assert_eq!(urn.parse::<MXFKey>(), Ok(key), "parse urn");
+        assert_eq!(
+            "060E2B34.0401010A.0D010301.02110200".parse::<MXFKey>(),
+            Ok(key),
+            "parse dotted hex"
+        );
+        assert!("060e2b34.0401010a".parse::<MXFKey>().is_err());
+        assert_eq!(key.as_bytes()[7], 0x0a, "as bytes");
+        assert!(key != other_version, "version octet differs");
+        assert!(key.eq_ignore_version(&other_version), "ignore version");
+        assert!(other_version < key, "ordering");
+    }
+
+    #[test]
+    fn can_recognise_avid_legacy_essence_elements() {
+        let avid_element = MXFKey::new(
+            0x06, 0x0e, 0x2b, 0x34, 0x01, 0x02, 0x01, 0x01, 0x0e, 0x04, 0x03, 0x01, 0x15, 0x01,
+            0x05, 0x01,
+        );
+        let index_segment = MXFKey::new(
+            0x06, 0x0e, 0x2b, 0x34, 0x02, 0x53, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x01, 0x10,
+            0x01, 0x00,
+        );
+
+        assert!(avid_element.is_essence_element(), "avid legacy element");
+        assert!(!index_segment.is_essence_element(), "index table segment");
+    }
+
+    #[test]
+    fn can_format_timecodes() {
+        let timecode = |frames, rounded_base, drop_frame| {
+            Timecode {
+                frames: frames,
+                rounded_base: rounded_base,
+                drop_frame: drop_frame,
+            }
+            .to_string()
+        };
+
+        assert_eq!(timecode(90000, 25, false), "01:00:00:00");
+        assert_eq!(timecode(107892, 30, true), "01:00:00;00");
+        assert_eq!(timecode(1800, 30, true), "00:01:00;02", "dropped frames");
+        assert_eq!(timecode(17982, 30, true), "00:10:00;00", "tenth minute");
+        assert_eq!(timecode(215784, 60, true), "01:00:00;00", "60 fps");
+        assert_eq!(timecode(1800, 30, false), "00:01:00:00", "non-drop");
+    }
+
+    #[test]
+    fn can_format_and_parse_umids() {
+        let umid = MXFUmid::new(
+            0x06, 0x0a, 0x2b, 0x34, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x0f, 0x00, 0x13, 0x00,
+            0x00, 0x00, 0x59, 0xcd, 0x01, 0x27, 0x87, 0x7c, 0x06, 0x63, 0x06, 0x0e, 0x2b, 0x34,
+            0x7f, 0x7f, 0x2a, 0x80,
+        );
+        let urn = "urn:smpte:umid:060a2b34.01010101.01010f00.13000000.\
+                   59cd0127.877c0663.060e2b34.7f7f2a80";
+        let avid = "060a2b340101010101010f001300000059cd0127877c0663060e2b347f7f2a80";
+
+        assert_eq!(umid.length(), 0x13, "length");
+        assert_eq!(umid.instance_number(), 0, "instance number");
+        assert_eq!(umid.universal_label()[10], 0x0f, "material type");
+        assert_eq!(umid.material_number()[0], 0x59, "material number");
+        assert_eq!(umid.to_string(), urn, "urn");
+        assert_eq!(umid.to_avid_string(), avid, "avid string");
+        assert_eq!(urn.parse::<MXFUmid>(), Ok(umid), "parse urn");
+        assert_eq!(avid.parse::<MXFUmid>(), Ok(umid), "parse avid string");
+        assert!("urn:smpte:umid:060a2b34".parse::<MXFUmid>().is_err());
+        assert!(MXFUmid::default() < umid, "ordering");
+    }
+
+    #[test]
+    fn can_convert_locators_to_paths() {
+        let locator = |kind, value: &str| Locator {
+            package_uid: None,
+            kind: kind,
+            value: value.to_string(),
+        };
+
+        assert_eq!(
+            locator(LocatorKind::Network, "file:///C:/Media/dom%20dom.mov").to_path(),
+            Some(PathBuf::from("C:/Media/dom dom.mov")),
+            "windows path"
+        );
+        assert_eq!(
+            locator(
+                LocatorKind::Network,
+                "file://localhost/Volumes/Media/domdom.mov"
+            )
+            .to_path(),
+            Some(PathBuf::from("/Volumes/Media/domdom.mov")),
+            "mac path"
+        );
+        assert_eq!(
+            locator(LocatorKind::Network, "http://example.com/domdom.mov").to_path(),
+            None,
+            "not a file url"
+        );
+        assert_eq!(
+            locator(LocatorKind::Network, "file://server/share/domdom.mov").to_path(),
+            None,
+            "remote host"
+        );
+        assert_eq!(
+            locator(LocatorKind::Text, "file:///C:/Media/domdom.mov").to_path(),
+            None,
+            "text locator"
+        );
+    }
+
+    #[test]
+    fn can_read_complete_metadata() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.V159CD0127V.mxf");
+        let header = MXFInfo::from_file(filename.as_path()).unwrap();
+        let complete =
+            MXFInfo::from_file_with_metadata(filename.as_path(), MetadataSource::Complete).unwrap();
+
+        assert_eq!(header.metadata_partition_offset, 0, "header metadata");
+        assert_eq!(complete.clip_name, header.clip_name, "clip name");
+        assert_eq!(
+            complete.track_duration, header.track_duration,
+            "track duration"
+        );
+        if header.header_closed && header.header_complete {
+            assert_eq!(
+                complete.metadata_partition_offset, 0,
+                "closed complete header"
+            );
+        }
+    }
+
+    #[test]
+    fn can_read_complete_metadata_of_open_header() {
+        let filename = temp_path("builder-open-header");
+        OpAtomBuilder::picture(Rational32::new(25, 1), 10)
+            .clip_name("capture")
+            .physical_package(PackageDescriptorType::Tape, "TAPE01")
+            .marker(4, "during capture", None)
+            .open_header(true)
+            .write(filename.as_path())
+            .unwrap();
+
+        let header = MXFInfo::from_file(filename.as_path()).unwrap();
+        assert!(!header.header_closed, "open header");
+        assert!(!header.header_complete, "incomplete header");
+        assert_eq!(header.metadata_partition_offset, 0, "header metadata");
+        assert_eq!(header.clip_duration, Some(0), "header duration");
+        assert!(header.markers.is_empty(), "no markers in header");
+
+        let complete =
+            MXFInfo::from_file_with_metadata(filename.as_path(), MetadataSource::Complete).unwrap();
+        let file = MXFFile::open(filename.as_path(), true).unwrap();
+        let footer_offset = file.footer_partition_offset().unwrap();
+        assert!(complete.metadata_partition_offset != 0, "footer metadata");
+        assert_eq!(
+            complete.metadata_partition_offset, footer_offset,
+            "footer partition"
+        );
+        assert_eq!(complete.clip_name, Some("capture".to_string()), "clip name");
+        assert_eq!(complete.clip_duration, Some(10), "footer duration");
+        assert_eq!(complete.container_duration, Some(10), "container duration");
+        assert_eq!(complete.markers.len(), 1, "footer markers");
+        assert_eq!(
+            file.partitions().unwrap().last().map(|p| p.this_partition),
+            Some(footer_offset),
+            "file position kept"
+        );
+        fs::remove_file(&filename).unwrap();
+    }
+
+    #[test]
+    fn can_list_partitions() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.V159CD0127V.mxf");
+        let file = MXFFile::open(filename.as_path(), true).unwrap();
+        let partitions = file.partitions().unwrap();
+
+        assert!(partitions.len() >= 2, "partition count");
+        assert_eq!(
+            partitions[0].kind,
+            PartitionKind::Header,
+            "header partition"
+        );
+        assert_eq!(partitions[0].this_partition, 0, "header offset");
+        let footer = partitions.last().unwrap();
+        assert_eq!(footer.kind, PartitionKind::Footer, "footer partition");
+        assert_eq!(
+            file.footer_partition_offset(),
+            Some(footer.this_partition),
+            "footer offset"
+        );
+    }
+
+    #[test]
+    fn can_parse_index_table_segments() {
+        let segment = IndexTableSegment::parse(&[
+            0x3f, 0x0b, 0x00, 0x08, 0, 0, 0, 25, 0, 0, 0, 1, // edit rate
+            0x3f, 0x0c, 0x00, 0x08, 0, 0, 0, 0, 0, 0, 0, 0, // start position
+            0x3f, 0x0d, 0x00, 0x08, 0, 0, 0, 0, 0, 0, 0, 2, // duration
+            0x3f, 0x05, 0x00, 0x04, 0, 0, 0, 0, // edit unit byte count
+            0x3f, 0x06, 0x00, 0x04, 0, 0, 0, 1, // index sid
+            0x3f, 0x07, 0x00, 0x04, 0, 0, 0, 2, // body sid
+            0x3f, 0x09, 0x00, 0x0e, 0, 0, 0, 1, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, // delta entries
+            0x3f, 0x0a, 0x00, 0x1e, 0, 0, 0, 2, 0, 0, 0, 11, // index entries
+            0x00, 0x00, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, // key frame at 0
+            0x01, 0xff, 0x00, 0, 0, 0, 0, 0, 0, 0x10, 0x00, // frame at 0x1000
+        ])
+        .unwrap();
+
+        assert_eq!(
+            segment.index_edit_rate,
+            Some(Rational32::new(25, 1)),
+            "edit rate"
+        );
+        assert_eq!(segment.index_duration, 2, "duration");
+        assert_eq!(segment.index_sid, 1, "index sid");
+        assert_eq!(segment.body_sid, 2, "body sid");
+        assert_eq!(segment.delta_entries.len(), 1, "delta entries");
+        assert_eq!(segment.index_entries.len(), 2, "index entries");
+        assert_eq!(segment.index_entries[0].flags, 0x80, "key frame flag");
+        assert_eq!(
+            segment.index_entries[1].temporal_offset, 1,
+            "temporal offset"
+        );
+        assert_eq!(
+            segment.index_entries[1].key_frame_offset, -1,
+            "key frame offset"
+        );
+
+        let table = IndexTable {
+            segments: vec![segment],
+            essence_offset: 0x200,
+            essence_length: 0x1800,
+        };
+        assert!(!table.is_cbr(), "vbr");
+        assert_eq!(table.frame_offset(0), Ok((0x200, 0x1000)), "first frame");
+        assert_eq!(table.frame_offset(1), Ok((0x1200, 0x800)), "last frame");
+        assert!(table.frame_offset(2).is_err(), "beyond the index");
+    }
+
+    #[test]
+    fn can_look_up_frame_offsets() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.A159CD0127A.mxf");
+        let file = MXFFile::open(filename.as_path(), true).unwrap();
+        let table = file.index_table().unwrap();
+
+        assert!(table.is_cbr(), "pcm is cbr");
+        let (offset, size) = table.frame_offset(0).unwrap();
+        assert_eq!(offset, table.essence_offset, "first frame offset");
+        assert_eq!(
+            file.frame_offset(1).unwrap(),
+            (offset + u64::from(size), size),
+            "second frame"
+        );
+    }
+
+    #[test]
+    fn can_read_essence() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.V159CD0127V.mxf");
+        let file = MXFFile::open(filename.as_path(), true).unwrap();
+        let frames: Vec<EssenceFrame> = EssenceReader::new(&file)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(frames.len(), 49, "frame count");
+        assert_eq!(frames[1].position, 1, "frame position");
+        assert!(!frames[0].data.is_empty(), "frame data");
+
+        let filename = sample_path.join("domdom.mov.A159CD0127A.mxf");
+        let file = MXFFile::open(filename.as_path(), true).unwrap();
+        let reader = EssenceReader::with_block_size(&file, 1920).unwrap();
+        let essence_length = reader.index_table().essence_length;
+        let blocks: Vec<EssenceFrame> = reader.collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(blocks[1].position, 1920, "block position");
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|block| block.data.len() as u64)
+                .sum::<u64>(),
+            essence_length,
+            "essence length"
+        );
+    }
+
+    #[test]
+    fn can_extract_wav() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.A159CD0127A.mxf");
+        let info = MXFInfo::from_file(filename.as_path()).unwrap();
+        let mut wav = Vec::new();
+        info.write_wav(filename.as_path(), &mut wav).unwrap();
+
+        assert_eq!(&wav[0..4], b"RIFF", "riff header");
+        assert_eq!(&wav[8..12], b"WAVE", "wave header");
+        assert_eq!(&wav[12..16], b"bext", "bext chunk");
+        assert_eq!(&wav[20..30], b"domdom.mov", "bext description");
+        let fmt = 12 + 8 + 602;
+        assert_eq!(&wav[fmt..fmt + 4], b"fmt ", "fmt chunk");
+        assert_eq!(wav[fmt + 10], 1, "channel count");
+        assert_eq!(wav[fmt + 22], 24, "bits per sample");
+        assert_eq!(&wav[fmt + 24..fmt + 28], b"data", "data chunk");
+    }
+
+    #[test]
+    fn can_extract_wav_from_aes3() {
+        let filename = temp_path("wav-aes3");
+        OpAtomBuilder::sound(Rational32::new(48000, 1), 480)
+            .channel_count(2)
+            .quantization_bits(24)
+            .essence_container("AES3 Clip-wrapped")
+            .write(filename.as_path())
+            .unwrap();
+        let info = MXFInfo::from_file(filename.as_path()).unwrap();
+        let mut wav = Vec::new();
+        info.write_wav(filename.as_path(), &mut wav).unwrap();
+        fs::remove_file(&filename).unwrap();
+
+        let fmt = 12 + 8 + 602;
+        assert_eq!(&wav[fmt..fmt + 4], b"fmt ", "fmt chunk");
+        assert_eq!(wav[fmt + 10], 2, "channel count");
+        assert_eq!(wav[fmt + 22], 24, "bits per sample");
+        assert_eq!(&wav[fmt + 24..fmt + 28], b"data", "data chunk");
+        let data = fmt + 32;
+        assert_eq!(
+            &wav[data - 4..data],
+            &(480u32 * 6).to_le_bytes(),
+            "data size"
+        );
+        assert_eq!(wav.len(), data + 480 * 6, "samples");
+
+        let d10 = MXFInfo {
+            audio_sampling_rate: Some(Rational32::new(48000, 1)),
+            channel_count: Some(1),
+            quantization_bits: Some(24),
+            essence_container_label: MXFKey::from_name("D-10 50Mbps 625/50i Frame-wrapped"),
+            ..Default::default()
+        };
+        assert_eq!(
+            d10.write_wav(&PathBuf::from("d10.mxf"), &mut Vec::new()),
+            Err("Only clip-wrapped BWF and AES3 audio is supported.".to_string())
+        );
+    }
+
+    #[test]
+    fn can_probe_file() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.V159CD0127V.mxf");
+        let info = MXFInfo::from_file(filename.as_path()).unwrap();
+        let probe = MXFInfo::probe(filename.as_path()).unwrap();
+
+        assert!(probe.is_op_atom, "op atom");
+        assert_eq!(
+            probe.material_package_uid, info.material_package_uid,
+            "material package uid"
+        );
+        assert_eq!(
+            probe.file_source_package_uid, info.file_source_package_uid,
+            "file package uid"
+        );
+        assert_eq!(probe.clip_name, info.clip_name, "clip name");
+        assert_eq!(probe.edit_rate, info.clip_edit_rate, "edit rate");
+        assert_eq!(probe.duration, info.track_duration, "duration");
+    }
+
+    #[test]
+    fn can_share_data_model() {
+        let sample_path = PathBuf::from("samples");
+        let model = Arc::new(DataModel::new().unwrap());
+        for name in &["domdom.mov.V159CD0127V.mxf", "domdom.mov.A159CD0127A.mxf"] {
+            let filename = sample_path.join(name);
+            let info = MXFInfo::from_file(filename.as_path()).unwrap();
+            let shared =
+                MXFInfo::from_file_with_model(filename.as_path(), MetadataSource::Header, &model)
+                    .unwrap();
+
+            assert_eq!(
+                shared.material_package_uid, info.material_package_uid,
+                "material package uid"
+            );
+            assert_eq!(shared.clip_name, info.clip_name, "clip name");
+            assert_eq!(shared.track_duration, info.track_duration, "duration");
+            let probe = MXFInfo::probe_with_model(filename.as_path(), &model).unwrap();
+            assert_eq!(probe.clip_name, info.clip_name, "probed clip name");
+        }
+        assert_eq!(Arc::strong_count(&model), 1, "model released by files");
+    }
+
+    /* Unique per process and test, so parallel test runs don't share fixtures */
+    fn temp_path(test_name: &str) -> PathBuf {
+        env::temp_dir().join(format!("mxfinfo-{}-{}.mxf", std::process::id(), test_name))
+    }
+
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    #[test]
+    fn can_send_types_across_threads() {
+        assert_send::<MXFFile>();
+        assert_send::<DataModel>();
+        assert_sync::<DataModel>();
+        assert_send::<MXFInfo>();
+        assert_sync::<MXFInfo>();
+        assert_send::<ProbeInfo>();
+        assert_sync::<ProbeInfo>();
+        assert_send::<IndexTable>();
+        assert_sync::<IndexTable>();
+        assert_send::<PartitionInfo>();
+        assert_sync::<PartitionInfo>();
+    }
+
+    #[test]
+    fn can_read_files_concurrently() {
+        let sample_path = PathBuf::from("samples");
+        let filenames = vec![
+            sample_path.join("domdom.mov.V159CD0127V.mxf"),
+            sample_path.join("domdom.mov.A159CD0127A.mxf"),
+        ];
+        let expected: Vec<MXFInfo> = filenames
+            .iter()
+            .map(|filename| MXFInfo::from_file(filename.as_path()).unwrap())
+            .collect();
+        let model = Arc::new(DataModel::new().unwrap());
+
+        let threads: Vec<_> = (0..16)
+            .map(|i| {
+                let filename = filenames[i % filenames.len()].clone();
+                let model = model.clone();
+                thread::spawn(move || {
+                    if i % 4 == 0 {
+                        MXFInfo::from_file_with_model(
+                            filename.as_path(),
+                            MetadataSource::Header,
+                            &model,
+                        )
+                        .unwrap()
+                    } else {
+                        MXFInfo::from_file(filename.as_path()).unwrap()
+                    }
+                })
+            })
+            .collect();
+        for (i, handle) in threads.into_iter().enumerate() {
+            let info = handle.join().unwrap();
+            let expected = &expected[i % filenames.len()];
+            assert_eq!(
+                info.material_package_uid, expected.material_package_uid,
+                "material package uid"
+            );
+            assert_eq!(info.clip_name, expected.clip_name, "clip name");
+            assert_eq!(info.track_duration, expected.track_duration, "duration");
+        }
+    }
+
+    #[test]
+    fn can_move_open_file_to_thread() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.A159CD0127A.mxf");
+        let file = MXFFile::open(filename.as_path(), true).unwrap();
+        let partitions = file.partitions().unwrap();
+
+        let moved = thread::spawn(move || file.partitions().unwrap())
+            .join()
+            .unwrap();
+        assert_eq!(moved, partitions, "partitions");
+    }
+
+    #[test]
+    fn can_compute_header_metadata_size() {
+        let mut prefix = vec![
+            0x06, 0x0e, 0x2b, 0x34, 0x02, 0x05, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x01, 0x02,
+            0x04, 0x00, 0x83, 0x00, 0x00, 0x58,
+        ];
+        prefix.extend_from_slice(&[0; 32]);
+        prefix.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0x10, 0x00]);
+        prefix.resize(65, 0);
+        assert_eq!(
+            MXFFile::header_metadata_size(&prefix),
+            Ok(16 + 4 + 88 + 0x1000),
+            "long form length"
+        );
+
+        let mut short = prefix.clone();
+        short.drain(17..20);
+        short[16] = 0x58;
+        short.resize(65, 0);
+        assert_eq!(
+            MXFFile::header_metadata_size(&short),
+            Ok(16 + 1 + 88 + 0x1000),
+            "short form length"
+        );
+
+        prefix[13] = 0x04;
+        assert!(
+            MXFFile::header_metadata_size(&prefix).is_err(),
+            "footer partition"
+        );
+        assert!(
+            MXFFile::header_metadata_size(&short[..40]).is_err(),
+            "truncated"
+        );
+    }
+
+    #[test]
+    fn can_read_info_from_memory() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.V159CD0127V.mxf");
+        let info = MXFInfo::from_file(filename.as_path()).unwrap();
+        let data = fs::read(&filename).unwrap();
+        let size = MXFFile::header_metadata_size(&data).unwrap() as usize;
+        let model = Arc::new(DataModel::new().unwrap());
+        let memory = MXFInfo::from_memory(data[..size].to_vec(), &model).unwrap();
+
+        assert_eq!(
+            memory.material_package_uid, info.material_package_uid,
+            "material package uid"
+        );
+        assert_eq!(memory.clip_name, info.clip_name, "clip name");
+        assert_eq!(memory.track_duration, info.track_duration, "duration");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn can_read_info_async() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.V159CD0127V.mxf");
+        let info = MXFInfo::from_file(filename.as_path()).unwrap();
+        let model = Arc::new(DataModel::new().unwrap());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        let async_info = runtime
+            .block_on(MXFInfo::from_path_async(filename.clone(), model.clone()))
+            .unwrap();
+        assert_eq!(
+            async_info.material_package_uid, info.material_package_uid,
+            "material package uid"
+        );
+        assert_eq!(async_info.clip_name, info.clip_name, "clip name");
+
+        let scanned = runtime
+            .block_on(async {
+                let stream = MXFInfo::scan_directory_async(sample_path, 2, model).await?;
+                Ok::<_, String>(stream.collect::<Vec<_>>().await)
+            })
+            .unwrap();
+        assert!(
+            scanned
+                .iter()
+                .any(|&(ref path, ref result)| *path == filename && result.is_ok()),
+            "scanned sample"
+        );
+    }
+
+    #[test]
+    fn can_update_metadata_in_place() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("domdom.mov.A159CD0127A.mxf");
+        let copy = temp_path("update");
+        fs::copy(&filename, &copy).unwrap();
+        let size = fs::metadata(&copy).unwrap().len();
+
+        /* Sets and items the data model does not know are kept */
+        let dark = MXFInfo::read_dark_metadata(copy.as_path()).unwrap();
+        let set_count = MXFFile::open(copy.as_path(), true)
+            .unwrap()
+            .dump()
+            .sets
+            .len();
+        let rename = MetadataUpdate {
+            clip_name: Some("renamed".to_string()),
+            ..MetadataUpdate::default()
+        };
+        MXFInfo::update_metadata(copy.as_path(), &rename).unwrap();
+        assert_eq!(
+            MXFInfo::read_dark_metadata(copy.as_path()).unwrap(),
+            dark,
+            "dark metadata"
+        );
+        assert_eq!(
+            MXFFile::open(copy.as_path(), true)
+                .unwrap()
+                .dump()
+                .sets
+                .len(),
+            set_count,
+            "set count"
+        );
+
+        let update = MetadataUpdate {
+            clip_name: Some("renamed".to_string()),
+            project_name: Some("fixed project".to_string()),
+            user_comments: vec![("Comments".to_string(), "checked".to_string())],
+            mob_attributes: vec![("_INGEST".to_string(), "batch 7".to_string())],
+        };
+        let info = MXFInfo::update_metadata(copy.as_path(), &update).unwrap();
+        assert_eq!(info.clip_name, Some("renamed".to_string()), "clip name");
+        assert_eq!(
+            info.project_name,
+            Some("fixed project".to_string()),
+            "project name"
+        );
+        assert!(
+            info.mob_attributes
+                .contains(&("_INGEST".to_string(), "batch 7".to_string())),
+            "mob attribute"
+        );
+        assert_eq!(fs::metadata(&copy).unwrap().len(), size, "file size");
+
+        let complete =
+            MXFInfo::from_file_with_metadata(copy.as_path(), MetadataSource::Complete).unwrap();
+        assert_eq!(
+            complete.clip_name,
+            Some("renamed".to_string()),
+            "footer clip name"
+        );
+
+        let too_long = MetadataUpdate {
+            clip_name: Some("x".repeat(30000)),
+            ..MetadataUpdate::default()
+        };
+        assert!(
+            MXFInfo::update_metadata(copy.as_path(), &too_long).is_err(),
+            "does not fit"
+        );
+        let info = MXFInfo::from_file(copy.as_path()).unwrap();
+        assert_eq!(info.clip_name, Some("renamed".to_string()), "unchanged");
+        fs::remove_file(&copy).unwrap();
+    }
+
+    #[test]
+    fn can_encode_index_table_segments() {
+        let segment = IndexTableSegment {
+            index_edit_rate: Some(Rational32::new(30000, 1001)),
+            index_start_position: 0,
+            index_duration: 2,
+            edit_unit_byte_count: 0,
+            index_sid: 1,
+            body_sid: 2,
+            slice_count: 1,
+            pos_table_count: 0,
+            delta_entries: vec![DeltaEntry {
+                pos_table_index: 0,
+                slice: 0,
+                element_delta: 0,
+            }],
+            index_entries: vec![
+                IndexEntry {
+                    temporal_offset: 0,
+                    key_frame_offset: 0,
+                    flags: 0x80,
+                    stream_offset: 0,
+                    slice_offsets: vec![0x200],
+                    pos_table: Vec::new(),
+                },
+                IndexEntry {
+                    temporal_offset: 1,
+                    key_frame_offset: -1,
+                    flags: 0x00,
+                    stream_offset: 0x1000,
+                    slice_offsets: vec![0x300],
+                    pos_table: Vec::new(),
+                },
+            ],
+        };
+        let data = segment.encode(&MXFKey::default());
+        assert_eq!(IndexTableSegment::parse(&data), Ok(segment), "round trip");
+    }
+
+    #[test]
+    fn can_build_op_atom_files() {
+        let filename = temp_path("builder-picture");
+        let created = NaiveDate::from_ymd(2019, 4, 1).and_hms(12, 30, 0);
+        OpAtomBuilder::picture(Rational32::new(30000, 1001), 10)
+            .clip_name("synthetic")
+            .project_name("fixtures")
+            .created(created)
+            .physical_package(PackageDescriptorType::Tape, "TAPE01")
+            .timecode(108000, 30, true)
+            .mob_attribute("_INGEST", "builder")
+            .write(filename.as_path())
+            .unwrap();
+
+        let info = MXFInfo::from_file(filename.as_path()).unwrap();
+        assert_eq!(info.clip_name, Some("synthetic".to_string()), "clip name");
+        assert_eq!(info.project_name, Some("fixtures".to_string()), "project");
+        assert_eq!(info.clip_created, Some(created), "created");
+        assert!(info.is_video, "video");
+        assert_eq!(info.clip_duration, Some(10), "duration");
+        assert_eq!(info.start_timecode, 108000, "start timecode");
+        let timecode = info.timecode.unwrap();
+        assert!(timecode.drop_frame, "drop frame");
+        assert_eq!(timecode.to_string(), "01:00:03;18", "drop frame timecode");
+        assert_eq!(
+            info.physical_package_name,
+            Some("TAPE01".to_string()),
+            "tape name"
+        );
+        assert!(
+            info.mob_attributes
+                .contains(&("_INGEST".to_string(), "builder".to_string())),
+            "mob attribute"
+        );
+        assert!(!info.is_truncated(), "complete essence");
+        assert!(
+            MXFInfo::read_dark_metadata(filename.as_path())
+                .unwrap()
+                .is_empty(),
+            "no dark metadata"
+        );
+        fs::remove_file(&filename).unwrap();
+    }
+
+    #[test]
+    fn can_build_essence_groups_without_physical_package() {
+        let filename = temp_path("builder-sound");
+        OpAtomBuilder::sound(Rational32::new(48000, 1), 48000)
+            .channel_count(1)
+            .quantization_bits(24)
+            .essence_group(true)
+            .write(filename.as_path())
+            .unwrap();
+
+        let info = MXFInfo::from_file(filename.as_path()).unwrap();
+        assert!(!info.is_video, "audio");
+        assert_eq!(info.block_align, Some(3), "block align");
+        assert_eq!(
+            info.clip_duration,
+            Some(25),
+            "duration in project edit units"
+        );
+        assert_eq!(
+            info.physical_source_package_uid, None,
+            "no physical package"
+        );
+        assert_eq!(info.start_timecode, 0, "no timecode");
+        let group = info.essence_group.expect("essence group");
+        assert_eq!(group.choices.len(), 1, "choices");
+        assert_eq!(
+            Some(group.choices[0].source_package_uid),
+            info.file_source_package_uid,
+            "choice references file package"
+        );
+        fs::remove_file(&filename).unwrap();
+
+        assert!(
+            OpAtomBuilder::sound(Rational32::new(48000, 1), 1)
+                .timecode(0, 25, false)
+                .write(filename.as_path())
+                .is_err(),
+            "timecode without physical package"
+        );
+    }
+
+    #[test]
+    fn can_classify_media_kinds() {
+        let media_kind = |test_name: &str, builder: OpAtomBuilder| {
+            let filename = temp_path(test_name);
+            builder.write(filename.as_path()).unwrap();
+            let info = MXFInfo::from_file(filename.as_path()).unwrap();
+            fs::remove_file(&filename).unwrap();
+            info.media_kind()
+        };
+        let picture = || OpAtomBuilder::picture(Rational32::new(25, 1), 10);
+
+        assert_eq!(
+            media_kind(
+                "kind-captured",
+                picture().physical_package(PackageDescriptorType::Tape, "TAPE01")
+            ),
+            MediaKind::Captured,
+            "captured"
+        );
+        assert_eq!(
+            media_kind(
+                "kind-imported",
+                picture().physical_package(PackageDescriptorType::Import, "clip.mov")
+            ),
+            MediaKind::Imported,
+            "imported"
+        );
+        assert_eq!(
+            media_kind(
+                "kind-consolidated",
+                picture()
+                    .clip_name("clip.new.01")
+                    .physical_package(PackageDescriptorType::Tape, "TAPE01")
+            ),
+            MediaKind::Consolidated,
+            "consolidated"
+        );
+        assert_eq!(
+            media_kind(
+                "kind-transcoded",
+                picture()
+                    .clip_name("clip.new.01")
+                    .physical_package(PackageDescriptorType::Picture, "clip")
+            ),
+            MediaKind::Transcoded,
+            "transcoded"
+        );
+        assert_eq!(
+            media_kind(
+                "kind-effect",
+                picture()
+                    .clip_name("Dissolve")
+                    .physical_package(PackageDescriptorType::Import, "Precompute Source Mob")
+            ),
+            MediaKind::VideoRender,
+            "effect render"
+        );
+        assert_eq!(
+            media_kind(
+                "kind-precompute",
+                picture().physical_package(PackageDescriptorType::Import, "Precompute Source Mob")
+            ),
+            MediaKind::VideoRender,
+            "precompute"
+        );
+        assert_eq!(
+            media_kind(
+                "kind-audio-render",
+                OpAtomBuilder::sound(Rational32::new(48000, 1), 480)
+                    .physical_package(PackageDescriptorType::Import, "Precompute Source Mob")
+            ),
+            MediaKind::AudioRender,
+            "audio render"
+        );
+        assert_eq!(
+            media_kind("kind-unknown", picture()),
+            MediaKind::Unknown,
+            "no physical package"
+        );
+        assert_eq!(
+            media_kind("kind-new-unknown", picture().clip_name("clip.new.01")),
+            MediaKind::Unknown,
+            "new master clip without source"
+        );
+        assert_eq!(
+            media_kind(
+                "kind-new-name",
+                picture()
+                    .clip_name("clip.new.final")
+                    .physical_package(PackageDescriptorType::Tape, "TAPE01")
+            ),
+            MediaKind::Captured,
+            "name without numeric suffix"
+        );
+
+        /* The chain is followed from the file package, wherever it starts */
+        let hop = |descriptor_type| SourcePackageHop {
+            descriptor_type: descriptor_type,
+            ..Default::default()
+        };
+        let info = MXFInfo {
+            clip_name: Some("clip.new.02".to_string()),
+            source_chain: vec![
+                hop(PackageDescriptorType::Material),
+                hop(PackageDescriptorType::Material),
+                hop(PackageDescriptorType::Picture),
+                hop(PackageDescriptorType::Tape),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            info.media_kind(),
+            MediaKind::Consolidated,
+            "nested material packages"
+        );
+    }
+
+    #[test]
+    fn can_read_markers() {
+        let filename = temp_path("builder-markers");
+        let red = RGBColor {
+            red: 0xffff,
+            green: 0,
+            blue: 0,
+        };
+        OpAtomBuilder::picture(Rational32::new(25, 1), 50)
+            .physical_package(PackageDescriptorType::Tape, "TAPE01")
+            .timecode(90000, 25, false)
+            .marker(12, "first", Some(red))
+            .marker(40, "second", None)
+            .write(filename.as_path())
+            .unwrap();
+
+        let info = MXFInfo::from_file(filename.as_path()).unwrap();
+        assert_eq!(info.markers.len(), 2, "markers");
+        assert_eq!(info.markers[0].position, 12, "position");
+        assert_eq!(info.markers[0].timecode, 90012, "timecode");
+        assert_eq!(
+            info.markers[0].comment,
+            Some("first".to_string()),
+            "comment"
+        );
+        assert_eq!(info.markers[0].color, Some(red), "color");
+        assert_eq!(info.markers[0].track_ids, vec![1], "track ids");
+        assert_eq!(info.markers[1].position, 40, "second position");
+        assert_eq!(
+            info.markers[1].comment,
+            Some("second".to_string()),
+            "second comment"
+        );
+        assert_eq!(info.markers[1].color, None, "no color");
+        fs::remove_file(&filename).unwrap();
+    }
+
+    #[test]
+    fn can_render_metadata_dump() {
+        let key = MXFKey::from_name("OP-Atom").unwrap();
+        let dump = MetadataDump {
+            sets: vec![DumpSet {
+                key: key,
+                class_name: Some("Preface".to_string()),
+                instance_uid: MXFKey::default(),
+                items: vec![
+                    DumpItem {
+                        key: key,
+                        tag: 0x3b09,
+                        name: Some("OperationalPattern".to_string()),
+                        type_name: Some("UL".to_string()),
+                        value: DumpValue::Key(key),
+                    },
+                    DumpItem {
+                        key: MXFKey::default(),
+                        tag: 0x8001,
+                        name: None,
+                        type_name: None,
+                        value: DumpValue::Bytes(vec![0x01, 0xff]),
+                    },
+                    DumpItem {
+                        key: key,
+                        tag: 0x3b08,
+                        name: Some("ProjectName".to_string()),
+                        type_name: Some("UTF16String".to_string()),
+                        value: DumpValue::String("say \"hi\"".to_string()),
+                    },
+                ],
+            }],
+        };
+
+        assert_eq!(
+            dump.to_string(),
+            format!(
+                "Preface [{0}]\n    OperationalPattern: {0} (OP-Atom)\n    \
+                 Unknown item 0x8001 [{1}]: 01ff\n    ProjectName: say \"hi\"\n",
+                key,
+                MXFKey::default()
+            ),
+            "text"
+        );
+        assert_eq!(
+            dump.to_json(),
+            format!(
+                "[{{\"class\":\"Preface\",\"key\":\"{0}\",\"instance_uid\":\"{1}\",\"items\":[\
+                 {{\"name\":\"OperationalPattern\",\"key\":\"{0}\",\"tag\":15113,\"type\":\"UL\",\
+                 \"value\":\"{0}\"}},\
+                 {{\"name\":null,\"key\":\"{1}\",\"tag\":32769,\"type\":null,\"value\":\"01ff\"}},\
+                 {{\"name\":\"ProjectName\",\"key\":\"{0}\",\"tag\":15112,\"type\":\"UTF16String\",\
+                 \"value\":\"say \\\"hi\\\"\"}}]}}]",
+                key,
+                MXFKey::default()
+            ),
+            "json"
+        );
+    }
+
+    #[test]
+    fn can_collect_dark_metadata() {
+        let known_set = [
+            0x06, 0x0e, 0x2b, 0x34, 0x02, 0x53, 0x01, 0x01, 0x0d, 0x01, 0x01, 0x01, 0x01, 0x01,
+            0x2f, 0x00,
+        ];
+        let dark_set = [
+            0x06, 0x0e, 0x2b, 0x34, 0x02, 0x53, 0x01, 0x01, 0x0e, 0x04, 0x01, 0x01, 0x01, 0x01,
+            0x01, 0x00,
+        ];
+        let known_item = [
+            0x06, 0x0e, 0x2b, 0x34, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x15, 0x02, 0x00, 0x00,
+            0x00, 0x00,
+        ];
+        let dark_item = [
+            0x06, 0x0e, 0x2b, 0x34, 0x01, 0x01, 0x01, 0x01, 0x0e, 0x04, 0x01, 0x01, 0x01, 0x01,
+            0x02, 0x00,
+        ];
+        let instance_uid = [0x11; 16];
+        let is_registered =
+            |key: &MXFKey| *key == MXFKey::from(known_set) || *key == MXFKey::from(known_item);
+
+        let mut data = vec![
+            0x06, 0x0e, 0x2b, 0x34, 0x02, 0x05, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x01, 0x05,
+            0x01, 0x00, 0x83, 0x00, 0x00, 44, 0, 0, 0, 2, 0, 0, 0, 18, 0x3c, 0x0a,
+        ];
+        data.extend_from_slice(&known_item);
+        data.extend_from_slice(&[0x80, 0x01]);
+        data.extend_from_slice(&dark_item);
+        /* Registered set with an unregistered item and an item missing from the primer */
+        data.extend_from_slice(&known_set);
+        data.extend_from_slice(&[30, 0x3c, 0x0a, 0, 16]);
+        data.extend_from_slice(&instance_uid);
+        data.extend_from_slice(&[0x80, 0x01, 0, 2, 0xab, 0xcd, 0x80, 0x02, 0, 0]);
+        /* KLV fill */
+        data.extend_from_slice(&[
+            0x06, 0x0e, 0x2b, 0x34, 0x01, 0x01, 0x01, 0x02, 0x03, 0x01, 0x02, 0x10, 0x01, 0x00,
+            0x00, 0x00, 3, 0, 0, 0,
+        ]);
+        /* Unregistered set */
+        data.extend_from_slice(&dark_set);
+        data.extend_from_slice(&[25, 0x3c, 0x0a, 0, 16]);
+        data.extend_from_slice(&instance_uid);
+        data.extend_from_slice(&[0x80, 0x01, 0, 1, 0x42]);
+        /* Truncated KLV */
+        data.extend_from_slice(&known_set[..8]);
+
+        let dark = DarkMetadata::from_header_metadata(&data, is_registered);
+        assert!(!dark.is_empty(), "dark metadata found");
+        assert_eq!(
+            dark.sets,
+            vec![DarkSet {
+                key: MXFKey::from(dark_set),
+                instance_uid: Some(MXFKey::from(instance_uid)),
+                items: vec![
+                    DarkItem {
+                        key: Some(MXFKey::from(known_item)),
+                        tag: 0x3c0a,
+                        value: instance_uid.to_vec(),
+                    },
+                    DarkItem {
+                        key: Some(MXFKey::from(dark_item)),
+                        tag: 0x8001,
+                        value: vec![0x42],
+                    },
+                ],
+            }],
+            "dark set"
+        );
+        assert_eq!(
+            dark.unknown_items,
+            vec![DarkSet {
+                key: MXFKey::from(known_set),
+                instance_uid: Some(MXFKey::from(instance_uid)),
+                items: vec![
+                    DarkItem {
+                        key: Some(MXFKey::from(dark_item)),
+                        tag: 0x8001,
+                        value: vec![0xab, 0xcd],
+                    },
+                    DarkItem {
+                        key: None,
+                        tag: 0x8002,
+                        value: vec![],
+                    },
+                ],
+            }],
+            "unknown items"
+        );
+        let dark = DarkMetadata::from_header_metadata(&data, |_| true);
+        assert!(dark.sets.is_empty(), "all sets registered");
+        assert_eq!(
+            dark.unknown_items[0].items.len(),
+            1,
+            "tag missing from primer"
+        );
+    }
+
+    #[test]
+    fn can_splice_dark_sets() {
+        let known_set = [
+            0x06, 0x0e, 0x2b, 0x34, 0x02, 0x53, 0x01, 0x01, 0x0d, 0x01, 0x01, 0x01, 0x01, 0x01,
+            0x2f, 0x00,
+        ];
+        let dark_set = [
+            0x06, 0x0e, 0x2b, 0x34, 0x02, 0x53, 0x01, 0x01, 0x0e, 0x04, 0x01, 0x01, 0x01, 0x01,
+            0x01, 0x00,
+        ];
+        let known_item = [
+            0x06, 0x0e, 0x2b, 0x34, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x15, 0x02, 0x00, 0x00,
+            0x00, 0x00,
+        ];
+        let dark_item = [
+            0x06, 0x0e, 0x2b, 0x34, 0x01, 0x01, 0x01, 0x01, 0x0e, 0x04, 0x01, 0x01, 0x01, 0x01,
+            0x02, 0x00,
+        ];
+        let is_registered =
+            |key: &MXFKey| *key == MXFKey::from(known_set) || *key == MXFKey::from(known_item);
+        let primer = |entries: &[(u16, [u8; 16])]| {
+            let mut data = vec![
+                0x06, 0x0e, 0x2b, 0x34, 0x02, 0x05, 0x01, 0x01, 0x0d, 0x01, 0x02, 0x01, 0x01, 0x05,
+                0x01, 0x00, 0x83, 0x00, 0x00,
+            ];
+            data.push(8 + 18 * entries.len() as u8);
+            data.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+            data.extend_from_slice(&18u32.to_be_bytes());
+            for &(tag, key) in entries {
+                data.extend_from_slice(&tag.to_be_bytes());
+                data.extend_from_slice(&key);
+            }
+            data
+        };
+        let fill = |len: u8| {
+            let mut data = vec![
+                0x06, 0x0e, 0x2b, 0x34, 0x01, 0x01, 0x01, 0x02, 0x03, 0x01, 0x02, 0x10, 0x01, 0x00,
+                0x00, 0x00, 0x83, 0x00, 0x00, len,
+            ];
+            data.resize(data.len() + len as usize, 0);
+            data
+        };
+        let mut known = known_set.to_vec();
+        known.extend_from_slice(&[20, 0x3c, 0x0a, 0, 16]);
+        known.extend_from_slice(&[0x11; 16]);
+
+        let mut original = primer(&[(0x3c0a, known_item), (0x8001, dark_item)]);
+        original.extend_from_slice(&known);
+        original.extend_from_slice(&dark_set);
+        original.extend_from_slice(&[25, 0x3c, 0x0a, 0, 16]);
+        original.extend_from_slice(&[0x22; 16]);
+        original.extend_from_slice(&[0x80, 0x01, 0, 1, 0x42]);
+        original.extend(fill(20));
+
+        let mut encoded = primer(&[(0x3c0a, known_item)]);
+        encoded.extend_from_slice(&known);
+        encoded.extend(fill(100));
+
+        let spliced = DarkMetadata::splice_dark_sets(&original, &encoded, is_registered).unwrap();
+        assert_eq!(spliced.len(), encoded.len(), "same size");
+        assert_eq!(
+            DarkMetadata::from_header_metadata(&spliced, is_registered),
+            DarkMetadata::from_header_metadata(&original, is_registered),
+            "dark set and primer entry kept"
+        );
+
+        /* The primer entry and the dark set take 60 of the fill bytes, leaving 145 to 147 bytes
+         * of fill with a four byte length */
+        for fill_len in 185..188 {
+            let mut encoded = primer(&[(0x3c0a, known_item)]);
+            encoded.extend_from_slice(&known);
+            encoded.extend(fill(fill_len));
+            let remaining = fill_len as usize - 40;
+
+            let spliced =
+                DarkMetadata::splice_dark_sets(&original, &encoded, is_registered).unwrap();
+            assert_eq!(spliced.len(), encoded.len(), "same size");
+            let fill_start = spliced.len() - remaining;
+            assert_eq!(
+                spliced[fill_start..fill_start + 20],
+                fill((remaining - 20) as u8)[..20],
+                "fill with four byte length"
+            );
+            assert_eq!(
+                DarkMetadata::from_header_metadata(&spliced, is_registered),
+                DarkMetadata::from_header_metadata(&original, is_registered),
+                "dark set kept"
+            );
+        }
+
+        let mut small = primer(&[(0x3c0a, known_item)]);
+        small.extend_from_slice(&known);
+        small.extend(fill(10));
+        assert!(
+            DarkMetadata::splice_dark_sets(&original, &small, is_registered).is_err(),
+            "does not fit"
+        );
+
+        let mut conflict = primer(&[(0x3c0a, known_item), (0x8001, known_item)]);
+        conflict.extend_from_slice(&known);
+        conflict.extend(fill(100));
+        assert!(
+            DarkMetadata::splice_dark_sets(&original, &conflict, is_registered).is_err(),
+            "local tag taken"
+        );
+    }
+
+    #[test]
+    fn can_dump_header_metadata() {
+        let filename = temp_path("dump");
+        OpAtomBuilder::picture(Rational32::new(25, 1), 2)
+            .clip_name("dumped")
+            .physical_package(PackageDescriptorType::Tape, "TAPE02")
+            .write(filename.as_path())
+            .unwrap();
+
+        let dump = MXFFile::open(filename.as_path(), false).unwrap().dump();
+        assert_eq!(dump.sets.len(), 1, "single root");
+        assert_eq!(
+            dump.sets[0].class_name,
+            Some("Preface".to_string()),
+            "preface root"
+        );
+        let text = dump.to_string();
+        assert!(text.contains("MaterialPackage"), "expanded packages");
+        assert!(text.contains("dumped"), "clip name");
+        assert!(text.contains("TapeDescriptor"), "tape descriptor");
+        assert!(!text.contains("(unresolved)"), "all references resolved");
+        assert!(dump.to_json().contains("\"value\":\"TAPE02\""), "json");
+        fs::remove_file(&filename).unwrap();
     }
 
     #[test]
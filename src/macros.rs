@@ -45,3 +45,30 @@ macro_rules! mxf_item_definition {
         };
     };
 }
+
+macro_rules! mxf_label_definition {
+    ($label: expr, $o0: tt, $o1: tt, $o2: tt, $o3: tt, $o4: tt, $o5: tt, $o6: tt, $o7: tt,
+     $o8: tt, $o9: tt, $o10: tt, $o11: tt, $o12: tt, $o13: tt, $o14: tt, $o15: tt) => {
+        (
+            MXFKey {
+                octet0: $o0,
+                octet1: $o1,
+                octet2: $o2,
+                octet3: $o3,
+                octet4: $o4,
+                octet5: $o5,
+                octet6: $o6,
+                octet7: $o7,
+                octet8: $o8,
+                octet9: $o9,
+                octet10: $o10,
+                octet11: $o11,
+                octet12: $o12,
+                octet13: $o13,
+                octet14: $o14,
+                octet15: $o15,
+            },
+            $label,
+        )
+    };
+}